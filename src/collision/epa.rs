@@ -0,0 +1,294 @@
+use crate::collision::gjk::{self, Outcome, Vertex};
+use crate::collision::Support;
+use crate::geometry::Triangle;
+use crate::vector::Vector3;
+
+const MAX_ITERATIONS: usize = 128;
+/// Distance under which a new support point is considered to lie on the polytope
+const TOLERANCE: f32 = 1e-5;
+
+/// Minimum translation separating two overlapping shapes
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Penetration {
+    /// unit direction going from the first shape towards the second, moving the second shape by
+    /// normal * depth separates them
+    pub normal: Vector3,
+    pub depth: f32,
+    /// deepest point of the first shape inside the second
+    pub point_a: Vector3,
+    /// deepest point of the second shape inside the first
+    pub point_b: Vector3,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Face {
+    indices: [usize; 3],
+    normal: Vector3,
+    distance: f32,
+}
+
+impl Face {
+    fn new(vertices: &[Vertex], indices: [usize; 3]) -> Option<Self> {
+        let [a, b, c] = indices.map(|i| vertices[i].point);
+        let normal = Vector3::cross(b - a, c - a);
+        let length = normal.magnitude();
+
+        if length <= f32::EPSILON {
+            return None;
+        }
+
+        let normal = normal * length.recip();
+
+        Some(Self {
+            indices,
+            normal,
+            distance: Vector3::dot(normal, a).max(0.0),
+        })
+    }
+}
+
+/// Returns None when the shapes do not overlap
+pub fn penetration<A, B>(a: &A, b: &B) -> Option<Penetration>
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    let mut vertices = match gjk::run(a, b) {
+        Outcome::Separated(..) => return None,
+        Outcome::Intersecting(vertices) => vertices,
+    };
+
+    let faces = if expand(a, b, &mut vertices) {
+        tetrahedron_faces(&vertices)
+    } else {
+        None
+    };
+
+    let mut faces = match faces {
+        Some(faces) => faces,
+        None => {
+            // the Minkowski difference is flat or too small to give normals, the shapes are
+            // only touching
+            let contact = vertices[0];
+            return Some(Penetration {
+                normal: Vector3::up(),
+                depth: 0.0,
+                point_a: contact.a,
+                point_b: contact.b,
+            });
+        }
+    };
+
+    let mut closest = faces[0];
+
+    for _ in 0..MAX_ITERATIONS {
+        closest = *faces
+            .iter()
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            .unwrap();
+
+        let w = Vertex::new(a, b, closest.normal);
+        if Vector3::dot(w.point, closest.normal) - closest.distance <= TOLERANCE {
+            break;
+        }
+
+        let index = vertices.len();
+        vertices.push(w);
+
+        // remove the faces seen from the new point and keep the boundary of the resulting hole
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        faces.retain(|face| {
+            let visible =
+                Vector3::dot(face.normal, w.point - vertices[face.indices[0]].point) > 0.0;
+
+            if visible {
+                let [i, j, k] = face.indices;
+                for &(a, b) in [(i, j), (j, k), (k, i)].iter() {
+                    match horizon.iter().position(|&edge| edge == (b, a)) {
+                        Some(shared) => {
+                            horizon.swap_remove(shared);
+                        }
+                        None => horizon.push((a, b)),
+                    }
+                }
+            }
+
+            !visible
+        });
+
+        for (i, j) in horizon {
+            if let Some(face) = Face::new(&vertices, [i, j, index]) {
+                faces.push(face);
+            }
+        }
+
+        if faces.is_empty() {
+            break;
+        }
+    }
+
+    let [i, j, k] = closest.indices;
+    let projection = closest.normal * closest.distance;
+    let weights = Triangle::new(vertices[i].point, vertices[j].point, vertices[k].point)
+        .barycentric(projection);
+    let combine = |f: fn(&Vertex) -> Vector3| {
+        f(&vertices[i]) * weights.x + f(&vertices[j]) * weights.y + f(&vertices[k]) * weights.z
+    };
+
+    // moving b along the normal of the closest face pushes that face of a - b onto the origin
+    Some(Penetration {
+        normal: closest.normal,
+        depth: closest.distance,
+        point_a: combine(|v| v.a),
+        point_b: combine(|v| v.b),
+    })
+}
+
+/// Faces of the tetrahedron wound so that their normals point outwards, None when one of them
+/// is too small for a normal
+fn tetrahedron_faces(vertices: &[Vertex]) -> Option<Vec<Face>> {
+    let mut faces = Vec::new();
+
+    for &(i, j, k, l) in [(0, 1, 2, 3), (0, 3, 1, 2), (0, 2, 3, 1), (1, 3, 2, 0)].iter() {
+        let face = Face::new(vertices, [i, j, k])?;
+
+        // wind every face so that its normal points away from the opposite vertex
+        if Vector3::dot(face.normal, vertices[l].point - vertices[i].point) > 0.0 {
+            faces.push(Face::new(vertices, [i, k, j])?);
+        } else {
+            faces.push(face);
+        }
+    }
+
+    Some(faces)
+}
+
+/// Grows the simplex returned by GJK into a tetrahedron, returns false when the Minkowski
+/// difference has no volume
+fn expand<A, B>(a: &A, b: &B, vertices: &mut Vec<Vertex>) -> bool
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    let axes = [
+        Vector3::right(),
+        Vector3::left(),
+        Vector3::up(),
+        Vector3::down(),
+        Vector3::forward(),
+        Vector3::backward(),
+    ];
+
+    if vertices.len() == 1 {
+        let origin = vertices[0].point;
+        let found = axes.iter().map(|axis| Vertex::new(a, b, *axis)).find(|w| {
+            let d = w.point - origin;
+            Vector3::dot(d, d) > TOLERANCE * TOLERANCE
+        });
+
+        match found {
+            Some(w) => vertices.push(w),
+            None => return false,
+        }
+    }
+
+    if vertices.len() == 2 {
+        let line = (vertices[1].point - vertices[0].point).normalized();
+        let found = axes
+            .iter()
+            .map(|axis| Vector3::cross(line, *axis))
+            .filter(|direction| direction.magnitude() > TOLERANCE)
+            .flat_map(|direction| vec![direction, -direction])
+            .map(|direction| Vertex::new(a, b, direction))
+            .find(|w| {
+                let offset = w.point - vertices[0].point;
+                Vector3::cross(offset, line).magnitude() > TOLERANCE
+            });
+
+        match found {
+            Some(w) => vertices.push(w),
+            None => return false,
+        }
+    }
+
+    if vertices.len() == 3 {
+        let normal = Vector3::cross(
+            vertices[1].point - vertices[0].point,
+            vertices[2].point - vertices[0].point,
+        )
+        .normalized();
+
+        let found = [normal, -normal]
+            .iter()
+            .map(|direction| Vertex::new(a, b, *direction))
+            .find(|w| Vector3::dot(w.point - vertices[0].point, normal).abs() > TOLERANCE);
+
+        match found {
+            Some(w) => vertices.push(w),
+            None => return false,
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::AABB;
+    use crate::geometry::{Capsule, Sphere};
+
+    #[test]
+    fn sphere_penetration() {
+        let a = Sphere::new(Vector3::zero(), 1.0);
+        let b = Sphere::new(Vector3::new(1.5, 0.0, 0.0), 1.0);
+        let result = penetration(&a, &b).unwrap();
+
+        assert!((result.depth - 0.5).abs() < 1e-2);
+        assert!((result.normal - Vector3::right()).magnitude() < 1e-2);
+        assert!((result.point_a - Vector3::new(1.0, 0.0, 0.0)).magnitude() < 1e-2);
+        assert!((result.point_b - Vector3::new(0.5, 0.0, 0.0)).magnitude() < 1e-2);
+    }
+
+    #[test]
+    fn box_penetration() {
+        let a = AABB::from_min_max(Vector3::zero(), Vector3::new(2.0, 2.0, 2.0));
+        let b = AABB::from_min_max(Vector3::new(0.5, 1.8, 0.5), Vector3::new(1.5, 3.0, 1.5));
+        let result = penetration(&a, &b).unwrap();
+
+        assert!((result.depth - 0.2).abs() < 1e-4);
+        assert!((result.normal - Vector3::up()).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn capsule_penetration() {
+        let floor = AABB::from_min_max(Vector3::new(-5.0, -1.0, -5.0), Vector3::new(5.0, 0.0, 5.0));
+        let capsule = Capsule::new(
+            Vector3::new(0.0, 0.3, 0.0),
+            Vector3::new(0.0, 2.0, 0.0),
+            0.5,
+        );
+        let result = penetration(&floor, &capsule).unwrap();
+
+        assert!((result.depth - 0.2).abs() < 1e-3);
+        assert!((result.normal - Vector3::up()).magnitude() < 1e-3);
+    }
+
+    #[test]
+    fn tiny_overlaps_still_penetrate() {
+        // the faces of the first tetrahedron are too small for a normal
+        let a = Sphere::new(Vector3::zero(), 1e-4);
+        let b = Sphere::new(Vector3::new(1e-4, 0.0, 0.0), 1e-4);
+        let result = penetration(&a, &b).unwrap();
+
+        assert!(result.depth >= 0.0 && result.depth < 2e-4);
+    }
+
+    #[test]
+    fn separated_shapes_do_not_penetrate() {
+        let a = Sphere::new(Vector3::zero(), 1.0);
+        let b = Sphere::new(Vector3::new(2.5, 0.0, 0.0), 1.0);
+
+        assert!(penetration(&a, &b).is_none());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::collision::Support;
+use crate::geometry::{Segment, Triangle};
+use crate::vector::Vector3;
+
+const MAX_ITERATIONS: usize = 64;
+/// Relative improvement of the distance under which the search is considered converged
+const TOLERANCE: f32 = 1e-6;
+/// Squared distance under which the shapes are considered touching
+const CONTACT_TOLERANCE: f32 = 1e-10;
+
+/// Point of the Minkowski difference a - b along with the points of a and b that produced it
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Vertex {
+    pub point: Vector3,
+    pub a: Vector3,
+    pub b: Vector3,
+}
+
+impl Vertex {
+    pub fn new<A, B>(a: &A, b: &B, direction: Vector3) -> Self
+    where
+        A: Support + ?Sized,
+        B: Support + ?Sized,
+    {
+        let (a, b) = (a.support(direction), b.support(-direction));
+        Self { point: a - b, a, b }
+    }
+}
+
+pub(crate) enum Outcome {
+    /// closest simplex of the Minkowski difference with the barycentric weights of the point
+    /// closest to the origin
+    Separated(Vec<Vertex>, Vec<f32>),
+    /// simplex enclosing, or touching, the origin
+    Intersecting(Vec<Vertex>),
+}
+
+/// Closest points between two separated shapes
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Distance {
+    pub distance: f32,
+    /// point on the surface of the first shape
+    pub point_a: Vector3,
+    /// point on the surface of the second shape
+    pub point_b: Vector3,
+}
+
+/// Returns None when the shapes overlap
+pub fn distance<A, B>(a: &A, b: &B) -> Option<Distance>
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    match run(a, b) {
+        Outcome::Intersecting(_) => None,
+        Outcome::Separated(vertices, weights) => {
+            let combine = |f: fn(&Vertex) -> Vector3| {
+                vertices
+                    .iter()
+                    .zip(weights.iter())
+                    .fold(Vector3::zero(), |acc, (v, w)| acc + f(v) * *w)
+            };
+
+            let (point_a, point_b) = (combine(|v| v.a), combine(|v| v.b));
+
+            Some(Distance {
+                distance: (point_a - point_b).magnitude(),
+                point_a,
+                point_b,
+            })
+        }
+    }
+}
+
+pub fn intersects<A, B>(a: &A, b: &B) -> bool
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    matches!(run(a, b), Outcome::Intersecting(_))
+}
+
+pub(crate) fn run<A, B>(a: &A, b: &B) -> Outcome
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    let mut vertices = vec![Vertex::new(a, b, Vector3::right())];
+
+    for iteration in 0..MAX_ITERATIONS {
+        let mut weights = match closest(&vertices) {
+            Some(weights) => weights,
+            None => return Outcome::Intersecting(vertices),
+        };
+
+        // only the vertices supporting the closest point are needed for the next step
+        let mut i = 0;
+        while i < vertices.len() {
+            if weights[i] <= 0.0 {
+                vertices.remove(i);
+                weights.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+
+        let v = vertices
+            .iter()
+            .zip(weights.iter())
+            .fold(Vector3::zero(), |acc, (vertex, w)| acc + vertex.point * *w);
+        let distance_squared = Vector3::dot(v, v);
+
+        if distance_squared <= CONTACT_TOLERANCE {
+            return Outcome::Intersecting(vertices);
+        }
+
+        let w = Vertex::new(a, b, -v);
+        let converged = distance_squared - Vector3::dot(v, w.point) <= TOLERANCE * distance_squared;
+        let duplicate = vertices.iter().any(|vertex| {
+            let d = vertex.point - w.point;
+            Vector3::dot(d, d) <= CONTACT_TOLERANCE
+        });
+
+        if converged || duplicate || iteration + 1 == MAX_ITERATIONS {
+            return Outcome::Separated(vertices, weights);
+        }
+
+        vertices.push(w);
+    }
+
+    unreachable!()
+}
+
+/// Barycentric weights of the point of the simplex closest to the origin, None when the
+/// simplex encloses the origin
+fn closest(vertices: &[Vertex]) -> Option<Vec<f32>> {
+    let p: Vec<Vector3> = vertices.iter().map(|v| v.point).collect();
+
+    match p.len() {
+        1 => Some(vec![1.0]),
+        2 => Some(closest_on_segment(p[0], p[1])),
+        3 => Some(closest_on_triangle(p[0], p[1], p[2])),
+        _ => closest_on_tetrahedron(&p),
+    }
+}
+
+fn closest_on_segment(a: Vector3, b: Vector3) -> Vec<f32> {
+    let t = Segment::new(a, b).closest_parameter(Vector3::zero());
+    vec![1.0 - t, t]
+}
+
+fn closest_on_triangle(a: Vector3, b: Vector3, c: Vector3) -> Vec<f32> {
+    let weights = Triangle::new(a, b, c).closest_barycentric(Vector3::zero());
+
+    if weights.x.is_finite() && weights.y.is_finite() && weights.z.is_finite() {
+        return vec![weights.x, weights.y, weights.z];
+    }
+
+    // a flat triangle is as good as its longest edge
+    let edges = [(0, 1), (1, 2), (0, 2)];
+    let points = [a, b, c];
+    let length = |(i, j): &(usize, usize)| {
+        let d = points[*j] - points[*i];
+        Vector3::dot(d, d)
+    };
+    let (i, j) = edges
+        .iter()
+        .filter(|edge| !length(edge).is_nan())
+        .max_by(|e0, e1| length(e0).total_cmp(&length(e1)))
+        .copied()
+        .unwrap_or(edges[0]);
+
+    let segment = closest_on_segment(points[i], points[j]);
+    let mut weights = vec![0.0; 3];
+    weights[i] = segment[0];
+    weights[j] = segment[1];
+    weights
+}
+
+fn closest_on_tetrahedron(p: &[Vector3]) -> Option<Vec<f32>> {
+    let faces = [(0, 1, 2, 3), (0, 1, 3, 2), (0, 2, 3, 1), (1, 2, 3, 0)];
+    let mut best: Option<(f32, Vec<f32>)> = None;
+
+    for &(i, j, k, l) in faces.iter() {
+        let normal = Vector3::cross(p[j] - p[i], p[k] - p[i]);
+        let side_origin = Vector3::dot(normal, -p[i]);
+        let side_opposite = Vector3::dot(normal, p[l] - p[i]);
+
+        // the origin is on the same side of this face as the rest of the tetrahedron
+        if side_origin * side_opposite > 0.0 {
+            continue;
+        }
+
+        let face = closest_on_triangle(p[i], p[j], p[k]);
+        let point = p[i] * face[0] + p[j] * face[1] + p[k] * face[2];
+        let distance_squared = Vector3::dot(point, point);
+
+        // is_none_or would need rust 1.82
+        #[allow(clippy::unnecessary_map_or)]
+        let closer = best.as_ref().map_or(true, |(d, _)| distance_squared < *d);
+        if closer {
+            let mut weights = vec![0.0; 4];
+            weights[i] = face[0];
+            weights[j] = face[1];
+            weights[k] = face[2];
+            best = Some((distance_squared, weights));
+        }
+    }
+
+    best.map(|(_, weights)| weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::{AABB, OBB};
+    use crate::geometry::{Capsule, ConvexHull, Sphere};
+    use crate::quaternion::Quaternion;
+
+    fn cube(center: Vector3, half: f32) -> ConvexHull {
+        let mut points = Vec::new();
+        for &x in [-half, half].iter() {
+            for &y in [-half, half].iter() {
+                for &z in [-half, half].iter() {
+                    points.push(center + Vector3::new(x, y, z));
+                }
+            }
+        }
+        ConvexHull::new(points)
+    }
+
+    #[test]
+    fn sphere_distance() {
+        let a = Sphere::new(Vector3::zero(), 1.0);
+        let b = Sphere::new(Vector3::new(3.0, 4.0, 0.0), 2.0);
+        let result = distance(&a, &b).unwrap();
+
+        assert!((result.distance - 2.0).abs() < 1e-3);
+        assert!((result.point_a - Vector3::new(0.6, 0.8, 0.0)).magnitude() < 1e-2);
+        assert!((result.point_b - Vector3::new(1.8, 2.4, 0.0)).magnitude() < 1e-2);
+    }
+
+    #[test]
+    fn box_distance() {
+        let a = AABB::from_min_max(Vector3::zero(), Vector3::identity());
+        let b = cube(Vector3::new(3.5, 0.5, 0.5), 0.5);
+        let result = distance(&a, &b).unwrap();
+
+        assert!((result.distance - 2.0).abs() < 1e-5);
+        assert!((result.point_a.x - 1.0).abs() < 1e-5);
+        assert!((result.point_b.x - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn capsule_against_rotated_box() {
+        let capsule = Capsule::new(
+            Vector3::new(0.0, -2.0, 0.0),
+            Vector3::new(0.0, 2.0, 0.0),
+            0.5,
+        );
+        let rotation = Quaternion::from_axis_angle(Vector3::up(), std::f32::consts::FRAC_PI_4);
+
+        let near = OBB::new(Vector3::new(1.8, 0.0, 0.0), Vector3::identity(), rotation);
+        assert!(intersects(&capsule, &near));
+
+        let far = OBB::new(Vector3::new(2.0, 0.0, 0.0), Vector3::identity(), rotation);
+        let result = distance(&capsule, &far).unwrap();
+        assert!((result.distance - (2.0 - 2.0f32.sqrt() - 0.5)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn overlapping_shapes_intersect() {
+        let a = cube(Vector3::zero(), 1.0);
+        let b = Sphere::new(Vector3::new(1.5, 0.0, 0.0), 0.6);
+        assert!(intersects(&a, &b));
+        assert!(distance(&a, &b).is_none());
+
+        let b = Sphere::new(Vector3::new(1.5, 0.0, 0.0), 0.4);
+        assert!(!intersects(&a, &b));
+    }
+
+    #[test]
+    fn nan_points_are_ignored() {
+        let mut points = cube(Vector3::zero(), 1.0).points().to_vec();
+        points.push(Vector3::new(f32::NAN, 0.0, 0.0));
+        let a = ConvexHull::new(points);
+        let b = Sphere::new(Vector3::new(4.0, 0.0, 0.0), 1.0);
+
+        let result = distance(&a, &b).unwrap();
+        assert!((result.distance - 2.0).abs() < 1e-4);
+    }
+}
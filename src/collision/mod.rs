@@ -0,0 +1,7 @@
+mod epa;
+mod gjk;
+mod support;
+
+pub use self::epa::{penetration, Penetration};
+pub use self::gjk::{distance, intersects, Distance};
+pub use self::support::Support;
@@ -0,0 +1,87 @@
+use crate::container::{AABB, OBB};
+use crate::geometry::{Box, Capsule, ConvexHull, Segment, Sphere, Triangle};
+use crate::utils::ternary;
+use crate::vector::Vector3;
+
+/// Convex shape described by its support mapping, which is all the GJK and EPA algorithms need
+/// to know about it
+pub trait Support {
+    /// point of the shape furthest along the given direction
+    fn support(&self, direction: Vector3) -> Vector3;
+}
+
+/// unit direction, or an arbitrary one when the given direction is degenerate
+fn unit(direction: Vector3) -> Vector3 {
+    let length = direction.magnitude();
+    ternary(length > 0.0, direction * length.recip(), Vector3::right())
+}
+
+fn furthest(points: &[Vector3], direction: Vector3) -> Vector3 {
+    // NaN coordinates are never the furthest
+    points
+        .iter()
+        .copied()
+        .filter(|point| !Vector3::dot(*point, direction).is_nan())
+        .max_by(|a, b| Vector3::dot(*a, direction).total_cmp(&Vector3::dot(*b, direction)))
+        .unwrap_or(points[0])
+}
+
+impl Support for AABB {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        let (min, max) = (self.min(), self.max());
+
+        Vector3::new(
+            ternary(direction.x > 0.0, max.x, min.x),
+            ternary(direction.y > 0.0, max.y, min.y),
+            ternary(direction.z > 0.0, max.z, min.z),
+        )
+    }
+}
+
+impl Support for Box {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        AABB::new(*self).support(direction)
+    }
+}
+
+impl Support for OBB {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        let [ax, ay, az] = self.axes();
+        let sign = |axis: Vector3| ternary(Vector3::dot(axis, direction) > 0.0, 1.0, -1.0);
+
+        self.center
+            + ax * (sign(ax) * self.half_extents.x)
+            + ay * (sign(ay) * self.half_extents.y)
+            + az * (sign(az) * self.half_extents.z)
+    }
+}
+
+impl Support for Sphere {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        self.center + unit(direction) * self.radius
+    }
+}
+
+impl Support for Segment {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        furthest(&[self.start, self.end], direction)
+    }
+}
+
+impl Support for Capsule {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        self.segment.support(direction) + unit(direction) * self.radius
+    }
+}
+
+impl Support for Triangle {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        furthest(&[self.a, self.b, self.c], direction)
+    }
+}
+
+impl Support for ConvexHull {
+    fn support(&self, direction: Vector3) -> Vector3 {
+        furthest(self.points(), direction)
+    }
+}
@@ -1,8 +1,9 @@
-use crate::geometry::Box;
+use crate::geometry::{Box, Ray};
 use crate::utils::ternary;
 use crate::vector::Vector3;
 
 /// Axis-aligned bounding box
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct AABB {
     bounds: Box,
 }
@@ -12,6 +13,126 @@ impl AABB {
         Self { bounds }
     }
 
+    pub fn from_min_max(min: Vector3, max: Vector3) -> Self {
+        let size = max - min;
+        Self::new(Box::new(min, size.x, size.y, size.z))
+    }
+
+    pub fn from_center(center: Vector3, half_extents: Vector3) -> Self {
+        Self::from_min_max(center - half_extents, center + half_extents)
+    }
+
+    /// smallest box enclosing both boxes
+    pub fn union(a: &AABB, b: &AABB) -> Self {
+        Self::from_min_max(
+            Vector3::min(a.min(), b.min()),
+            Vector3::max(a.max(), b.max()),
+        )
+    }
+
+    /// box grown by margin on every side
+    pub fn expanded(&self, margin: f32) -> Self {
+        let margin = Vector3::identity() * margin;
+        Self::from_min_max(self.min() - margin, self.max() + margin)
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let size = self.bounds.size.abs();
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    pub fn bounds(&self) -> &Box {
+        &self.bounds
+    }
+
+    pub fn min(&self) -> Vector3 {
+        Vector3::min(self.bounds.origin, self.bounds.origin + self.bounds.size)
+    }
+
+    pub fn max(&self) -> Vector3 {
+        Vector3::max(self.bounds.origin, self.bounds.origin + self.bounds.size)
+    }
+
+    pub fn center(&self) -> Vector3 {
+        self.bounds.origin + self.bounds.size * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vector3 {
+        self.bounds.size.abs() * 0.5
+    }
+
+    pub fn contains(&self, point: Vector3) -> bool {
+        self.bounds.contains(point)
+    }
+
+    pub fn intersects(&self, other: &AABB) -> bool {
+        let (a_min, a_max) = (self.min(), self.max());
+        let (b_min, b_max) = (other.min(), other.max());
+
+        a_min.x <= b_max.x
+            && b_min.x <= a_max.x
+            && a_min.y <= b_max.y
+            && b_min.y <= a_max.y
+            && a_min.z <= b_max.z
+            && b_min.z <= a_max.z
+    }
+
+    /// whether the other box is entirely inside this one
+    pub fn encloses(&self, other: &AABB) -> bool {
+        let (a_min, a_max) = (self.min(), self.max());
+        let (b_min, b_max) = (other.min(), other.max());
+
+        a_min.x <= b_min.x
+            && a_min.y <= b_min.y
+            && a_min.z <= b_min.z
+            && b_max.x <= a_max.x
+            && b_max.y <= a_max.y
+            && b_max.z <= a_max.z
+    }
+
+    /// Distance along the ray at which it enters the box, 0.0 when it starts inside
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<f32> {
+        let (min, max) = (self.min(), self.max());
+        let mut near = 0.0f32;
+        let mut far = f32::INFINITY;
+
+        let axes = [
+            (ray.origin.x, ray.direction.x, min.x, max.x),
+            (ray.origin.y, ray.direction.y, min.y, max.y),
+            (ray.origin.z, ray.direction.z, min.z, max.z),
+        ];
+
+        for &(origin, direction, min, max) in axes.iter() {
+            if direction == 0.0 {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let inverse = direction.recip();
+            let (t0, t1) = ((min - origin) * inverse, (max - origin) * inverse);
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+
+            if near > far {
+                return None;
+            }
+        }
+
+        Some(near)
+    }
+
+    /// point inside or on the surface of the box closest to the given point
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        Vector3::min(Vector3::max(point, self.min()), self.max())
+    }
+
+    pub fn distance_squared(&self, point: Vector3) -> f32 {
+        let d = point - self.closest_point(point);
+        Vector3::dot(d, d)
+    }
+
     /// volume on the positive side of the plane
     pub fn vp(&self, normal: &Vector3) -> Vector3 {
         self.bounds.origin
@@ -22,13 +143,29 @@ impl AABB {
             }
     }
 
-    /// volume on the negative side of the plane
+    /// corner furthest along the opposite of the normal, it lies inside the box
     pub fn vn(&self, normal: &Vector3) -> Vector3 {
         self.bounds.origin
-            - Vector3 {
+            + Vector3 {
                 x: ternary(normal.x < 0.0, self.bounds.size.x, 0.0),
                 y: ternary(normal.y < 0.0, self.bounds.size.y, 0.0),
                 z: ternary(normal.z < 0.0, self.bounds.size.z, 0.0),
             }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plane_corners() {
+        let aabb = AABB::from_min_max(Vector3::zero(), Vector3::new(1.0, 2.0, 3.0));
+        let normal = Vector3::new(-1.0, 1.0, -1.0);
+
+        assert_eq!(aabb.vp(&normal), Vector3::new(0.0, 2.0, 0.0));
+        // used to be origin - (1, 0, 3), a point outside the box
+        assert_eq!(aabb.vn(&normal), Vector3::new(1.0, 0.0, 3.0));
+        assert_eq!(aabb.vn(&normal), aabb.vp(&-normal));
+    }
+}
@@ -0,0 +1,655 @@
+use crate::container::{Frustum, Intersection, AABB};
+use crate::geometry::Ray;
+use crate::vector::Vector3;
+
+/// Factor applied to the displacement when predicting where a moving box is heading
+const DISPLACEMENT_MULTIPLIER: f32 = 4.0;
+
+struct Leaf<T> {
+    value: T,
+    /// exact bounds, the node itself stores the fattened ones
+    aabb: AABB,
+}
+
+struct Node<T> {
+    aabb: AABB,
+    parent: Option<usize>,
+    children: Option<[usize; 2]>,
+    height: u32,
+    leaf: Option<Leaf<T>>,
+}
+
+/// Dynamic AABB tree, as used for the broadphase of physics engines
+///
+/// Leaves store fattened boxes so that small movements do not require updating the tree. Values
+/// are referred to by the key returned on insertion, keys of removed values are reused.
+pub struct Bvh<T> {
+    nodes: Vec<Option<Node<T>>>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    margin: f32,
+    len: usize,
+}
+
+impl<T> Bvh<T> {
+    /// margin: distance by which the boxes of the leaves are fattened
+    pub fn new(margin: f32) -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            margin,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// height of the tree, 0 when it only holds a leaf
+    pub fn height(&self) -> u32 {
+        self.root.map_or(0, |root| self.node(root).height)
+    }
+
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.leaf(key).map(|leaf| &leaf.value)
+    }
+
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.nodes
+            .get_mut(key)
+            .and_then(|node| node.as_mut())
+            .and_then(|node| node.leaf.as_mut())
+            .map(|leaf| &mut leaf.value)
+    }
+
+    pub fn aabb(&self, key: usize) -> Option<&AABB> {
+        self.leaf(key).map(|leaf| &leaf.aabb)
+    }
+
+    /// bounds stored in the tree for the value, enclosing its exact bounds
+    pub fn fat_aabb(&self, key: usize) -> Option<&AABB> {
+        self.leaf(key).map(|_| &self.node(key).aabb)
+    }
+
+    pub fn insert(&mut self, value: T, aabb: AABB) -> usize {
+        let key = self.allocate(Node {
+            aabb: aabb.expanded(self.margin),
+            parent: None,
+            children: None,
+            height: 0,
+            leaf: Some(Leaf { value, aabb }),
+        });
+
+        self.insert_leaf(key);
+        self.len += 1;
+        key
+    }
+
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        self.leaf(key)?;
+        self.remove_leaf(key);
+        self.len -= 1;
+
+        self.release(key).leaf.map(|leaf| leaf.value)
+    }
+
+    /// Moves a value to its new bounds, displacement being its movement since the last update
+    ///
+    /// Returns true when the value left its fattened box and had to be reinserted.
+    pub fn update(&mut self, key: usize, aabb: AABB, displacement: Vector3) -> bool {
+        if self.leaf(key).is_none() {
+            return false;
+        }
+
+        let node = self.node_mut(key);
+        node.leaf.as_mut().unwrap().aabb = aabb;
+
+        if node.aabb.encloses(&aabb) {
+            return false;
+        }
+
+        // extend the box towards where the value is heading
+        let fat = aabb.expanded(self.margin);
+        let d = displacement * DISPLACEMENT_MULTIPLIER;
+        let (mut min, mut max) = (fat.min(), fat.max());
+        min += Vector3::min(d, Vector3::zero());
+        max += Vector3::max(d, Vector3::zero());
+
+        self.remove_leaf(key);
+        self.node_mut(key).aabb = AABB::from_min_max(min, max);
+        self.insert_leaf(key);
+
+        true
+    }
+
+    /// keys of the values whose bounds intersect the region
+    pub fn query_aabb(&self, region: &AABB) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(index) = stack.pop() {
+            let node = self.node(index);
+
+            if !node.aabb.intersects(region) {
+                continue;
+            }
+
+            match (&node.leaf, node.children) {
+                (Some(leaf), _) if leaf.aabb.intersects(region) => result.push(index),
+                (_, Some(children)) => stack.extend(children.iter()),
+                _ => {}
+            }
+        }
+
+        result
+    }
+
+    /// keys of the values hit by the ray along with the hit distance, closest first
+    pub fn query_ray(&self, ray: &Ray, max_distance: f32) -> Vec<(usize, f32)> {
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(index) = stack.pop() {
+            let node = self.node(index);
+
+            match node.aabb.intersect_ray(ray) {
+                Some(t) if t <= max_distance => {}
+                _ => continue,
+            }
+
+            if let Some(leaf) = &node.leaf {
+                if let Some(t) = leaf.aabb.intersect_ray(ray) {
+                    if t <= max_distance {
+                        result.push((index, t));
+                    }
+                }
+            }
+
+            if let Some(children) = node.children {
+                stack.extend(children.iter());
+            }
+        }
+
+        result.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        result
+    }
+
+    /// keys of the values visible in the frustum
+    pub fn query_frustum(&self, frustum: &Frustum) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(index) = stack.pop() {
+            let node = self.node(index);
+
+            match frustum.classify(&node.aabb) {
+                Intersection::Outside => {}
+                Intersection::Inside => self.collect(index, &mut result),
+                Intersection::Intersecting => match (&node.leaf, node.children) {
+                    (Some(leaf), _) if frustum.contains(&leaf.aabb) => result.push(index),
+                    (_, Some(children)) => stack.extend(children.iter()),
+                    _ => {}
+                },
+            }
+        }
+
+        result
+    }
+
+    /// Every pair of keys whose bounds overlap, with the smallest key first
+    pub fn overlapping_pairs(&self) -> Vec<(usize, usize)> {
+        let mut result = Vec::new();
+
+        for (key, node) in self.nodes.iter().enumerate() {
+            let leaf = match node.as_ref().and_then(|node| node.leaf.as_ref()) {
+                Some(leaf) => leaf,
+                None => continue,
+            };
+
+            for other in self.query_aabb(&leaf.aabb) {
+                if key < other {
+                    result.push((key, other));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// every key stored in the subtree
+    fn collect(&self, index: usize, result: &mut Vec<usize>) {
+        let mut stack = vec![index];
+
+        while let Some(index) = stack.pop() {
+            let node = self.node(index);
+
+            match node.children {
+                Some(children) => stack.extend(children.iter()),
+                None => result.push(index),
+            }
+        }
+    }
+
+    fn insert_leaf(&mut self, leaf: usize) {
+        let root = match self.root {
+            Some(root) => root,
+            None => {
+                self.root = Some(leaf);
+                self.node_mut(leaf).parent = None;
+                return;
+            }
+        };
+
+        // find the sibling that increases the total surface area the least
+        let leaf_aabb = self.node(leaf).aabb;
+        let mut index = root;
+
+        while let Some(children) = self.node(index).children {
+            let area = self.node(index).aabb.surface_area();
+            let combined = AABB::union(&self.node(index).aabb, &leaf_aabb).surface_area();
+
+            // cost of creating a new parent for this node and the leaf
+            let cost = 2.0 * combined;
+            // minimum cost of pushing the leaf further down
+            let inheritance = 2.0 * (combined - area);
+
+            let child_cost = |child: usize| {
+                let node = self.node(child);
+                let area = AABB::union(&node.aabb, &leaf_aabb).surface_area();
+
+                match node.children {
+                    None => area + inheritance,
+                    Some(_) => area - node.aabb.surface_area() + inheritance,
+                }
+            };
+
+            let (cost0, cost1) = (child_cost(children[0]), child_cost(children[1]));
+
+            if cost < cost0 && cost < cost1 {
+                break;
+            }
+
+            index = if cost0 < cost1 {
+                children[0]
+            } else {
+                children[1]
+            };
+        }
+
+        let sibling = index;
+        let old_parent = self.node(sibling).parent;
+        let parent = self.allocate(Node {
+            aabb: AABB::union(&leaf_aabb, &self.node(sibling).aabb),
+            parent: old_parent,
+            children: Some([sibling, leaf]),
+            height: self.node(sibling).height + 1,
+            leaf: None,
+        });
+
+        match old_parent {
+            Some(old_parent) => self.replace_child(old_parent, sibling, parent),
+            None => self.root = Some(parent),
+        }
+
+        self.node_mut(sibling).parent = Some(parent);
+        self.node_mut(leaf).parent = Some(parent);
+
+        self.refit(Some(parent));
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        if self.root == Some(leaf) {
+            self.root = None;
+            return;
+        }
+
+        let parent = self.node(leaf).parent.unwrap();
+        let grand_parent = self.node(parent).parent;
+        let children = self.node(parent).children.unwrap();
+        let sibling = if children[0] == leaf {
+            children[1]
+        } else {
+            children[0]
+        };
+
+        self.release(parent);
+        self.node_mut(sibling).parent = grand_parent;
+
+        match grand_parent {
+            Some(grand_parent) => {
+                self.replace_child(grand_parent, parent, sibling);
+                self.refit(Some(grand_parent));
+            }
+            None => self.root = Some(sibling),
+        }
+    }
+
+    /// rebalances and recomputes the bounds of every ancestor, starting from index
+    fn refit(&mut self, mut index: Option<usize>) {
+        while let Some(current) = index {
+            let current = self.balance(current);
+            let [a, b] = self.node(current).children.unwrap();
+            let height = 1 + self.node(a).height.max(self.node(b).height);
+            let aabb = AABB::union(&self.node(a).aabb, &self.node(b).aabb);
+
+            let node = self.node_mut(current);
+            node.height = height;
+            node.aabb = aabb;
+            index = node.parent;
+        }
+    }
+
+    /// Rotates the tree when a child of a is taller than the other by more than one level,
+    /// returns the index of the node now at a's position
+    fn balance(&mut self, a: usize) -> usize {
+        let [b, c] = match self.node(a).children {
+            Some(children) if self.node(a).height >= 2 => children,
+            _ => return a,
+        };
+
+        let difference = self.node(c).height as i64 - self.node(b).height as i64;
+
+        if difference > 1 {
+            self.rotate(a, c, 1)
+        } else if difference < -1 {
+            self.rotate(a, b, 0)
+        } else {
+            a
+        }
+    }
+
+    /// Moves the child, at position side of a, up in place of a
+    fn rotate(&mut self, a: usize, child: usize, side: usize) -> usize {
+        let [f, g] = self.node(child).children.unwrap();
+        let other = self.node(a).children.unwrap()[1 - side];
+        let parent = self.node(a).parent;
+
+        // child takes the place of a, which becomes one of its children
+        self.node_mut(child).parent = parent;
+        self.node_mut(a).parent = Some(child);
+
+        match parent {
+            Some(parent) => self.replace_child(parent, a, child),
+            None => self.root = Some(child),
+        }
+
+        // the taller grandchild stays with child, the other one moves to a
+        let (kept, moved) = if self.node(f).height > self.node(g).height {
+            (f, g)
+        } else {
+            (g, f)
+        };
+
+        let mut children = [0; 2];
+        children[side] = moved;
+        children[1 - side] = other;
+
+        self.node_mut(child).children = Some([a, kept]);
+        self.node_mut(a).children = Some(children);
+        self.node_mut(moved).parent = Some(a);
+
+        let a_aabb = AABB::union(&self.node(other).aabb, &self.node(moved).aabb);
+        let a_height = 1 + self.node(other).height.max(self.node(moved).height);
+        let child_aabb = AABB::union(&a_aabb, &self.node(kept).aabb);
+        let child_height = 1 + a_height.max(self.node(kept).height);
+
+        let node = self.node_mut(a);
+        node.aabb = a_aabb;
+        node.height = a_height;
+
+        let node = self.node_mut(child);
+        node.aabb = child_aabb;
+        node.height = child_height;
+
+        child
+    }
+
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        let children = self.node_mut(parent).children.as_mut().unwrap();
+        let position = if children[0] == old { 0 } else { 1 };
+        children[position] = new;
+    }
+
+    fn allocate(&mut self, node: Node<T>) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = Some(node);
+                index
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, index: usize) -> Node<T> {
+        self.free.push(index);
+        self.nodes[index].take().unwrap()
+    }
+
+    fn leaf(&self, key: usize) -> Option<&Leaf<T>> {
+        self.nodes
+            .get(key)
+            .and_then(|node| node.as_ref())
+            .and_then(|node| node.leaf.as_ref())
+    }
+
+    fn node(&self, index: usize) -> &Node<T> {
+        self.nodes[index].as_ref().unwrap()
+    }
+
+    fn node_mut(&mut self, index: usize) -> &mut Node<T> {
+        self.nodes[index].as_mut().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::Matrix4;
+    use crate::random::{Prng, Seed};
+
+    fn random_aabb(prng: &mut Prng) -> AABB {
+        let center = Vector3::new(
+            prng.next_f32() * 200.0 - 100.0,
+            prng.next_f32() * 200.0 - 100.0,
+            prng.next_f32() * 200.0 - 100.0,
+        );
+        let size = prng.next_f32() * 8.0 + 0.1;
+        AABB::from_center(center, Vector3::identity() * size)
+    }
+
+    fn sorted(mut keys: Vec<usize>) -> Vec<usize> {
+        keys.sort_unstable();
+        keys
+    }
+
+    /// checks the links, heights and bounds of every node, returns the number of leaves
+    fn validate<T>(tree: &Bvh<T>, index: usize, parent: Option<usize>) -> usize {
+        let node = tree.node(index);
+        assert_eq!(node.parent, parent);
+
+        match node.children {
+            None => {
+                assert_eq!(node.height, 0);
+                assert!(node.aabb.encloses(&node.leaf.as_ref().unwrap().aabb));
+                1
+            }
+            Some([a, b]) => {
+                let (na, nb) = (tree.node(a), tree.node(b));
+                assert_eq!(node.height, 1 + na.height.max(nb.height));
+                // bounds are stored as origin and size, allow for rounding
+                let aabb = node.aabb.expanded(1e-4);
+                assert!(aabb.encloses(&na.aabb) && aabb.encloses(&nb.aabb));
+                validate(tree, a, Some(index)) + validate(tree, b, Some(index))
+            }
+        }
+    }
+
+    fn populated(prng: &mut Prng) -> (Bvh<usize>, Vec<AABB>) {
+        let mut tree = Bvh::new(0.5);
+        let mut boxes = Vec::new();
+
+        for i in 0..500 {
+            let aabb = random_aabb(prng);
+            let key = tree.insert(i, aabb);
+            assert_eq!(tree.get(key), Some(&i));
+            boxes.push(aabb);
+        }
+
+        (tree, boxes)
+    }
+
+    #[test]
+    fn stays_balanced() {
+        let mut tree = Bvh::new(0.1);
+
+        // sorted insertions degenerate into a list without rotations
+        for i in 0..1024 {
+            let center = Vector3::new(i as f32 * 2.0, 0.0, 0.0);
+            tree.insert(i, AABB::from_center(center, Vector3::identity() * 0.5));
+        }
+
+        assert_eq!(validate(&tree, tree.root.unwrap(), None), 1024);
+        assert!(tree.height() <= 20, "height {}", tree.height());
+    }
+
+    #[test]
+    fn query_aabb_matches_brute_force() {
+        let mut prng = Prng::new(Seed(53));
+        let (tree, boxes) = populated(&mut prng);
+
+        for _ in 0..50 {
+            let region = random_aabb(&mut prng);
+            let keys = sorted(tree.query_aabb(&region));
+            let values: Vec<usize> = keys.iter().map(|&key| *tree.get(key).unwrap()).collect();
+            let expected: Vec<usize> = (0..boxes.len())
+                .filter(|&i| boxes[i].intersects(&region))
+                .collect();
+
+            assert_eq!(sorted(values), expected);
+        }
+    }
+
+    #[test]
+    fn update_and_remove() {
+        let mut prng = Prng::new(Seed(59));
+        let mut tree = Bvh::new(0.5);
+        let mut keys = Vec::new();
+        let mut boxes = Vec::new();
+
+        for i in 0..300 {
+            let aabb = random_aabb(&mut prng);
+            keys.push(tree.insert(i, aabb));
+            boxes.push(aabb);
+        }
+
+        // small moves stay within the fattened boxes
+        let offset = Vector3::new(0.2, 0.0, 0.0);
+        for (key, aabb) in keys.iter().zip(boxes.iter_mut()) {
+            *aabb = AABB::from_min_max(aabb.min() + offset, aabb.max() + offset);
+            assert!(!tree.update(*key, *aabb, offset));
+        }
+
+        for (key, aabb) in keys.iter().zip(boxes.iter_mut()) {
+            let moved = random_aabb(&mut prng);
+            let displacement = moved.center() - aabb.center();
+            *aabb = moved;
+            assert!(tree.update(*key, moved, displacement));
+        }
+
+        for (i, key) in keys.iter().enumerate().take(100) {
+            assert_eq!(tree.remove(*key), Some(i));
+        }
+        assert_eq!(tree.remove(keys[0]), None);
+        assert_eq!(tree.len(), 200);
+        assert_eq!(validate(&tree, tree.root.unwrap(), None), 200);
+
+        let region = AABB::from_center(Vector3::zero(), Vector3::identity() * 50.0);
+        let values: Vec<usize> = tree
+            .query_aabb(&region)
+            .into_iter()
+            .map(|key| *tree.get(key).unwrap())
+            .collect();
+        let expected: Vec<usize> = (100..300)
+            .filter(|&i| boxes[i].intersects(&region))
+            .collect();
+
+        assert_eq!(sorted(values), expected);
+    }
+
+    #[test]
+    fn overlapping_pairs_match_brute_force() {
+        let mut prng = Prng::new(Seed(61));
+        let (tree, boxes) = populated(&mut prng);
+
+        let mut expected = Vec::new();
+        for i in 0..boxes.len() {
+            for j in i + 1..boxes.len() {
+                if boxes[i].intersects(&boxes[j]) {
+                    expected.push((i, j));
+                }
+            }
+        }
+
+        let mut pairs: Vec<(usize, usize)> = tree
+            .overlapping_pairs()
+            .into_iter()
+            .map(|(a, b)| {
+                let (a, b) = (*tree.get(a).unwrap(), *tree.get(b).unwrap());
+                (a.min(b), a.max(b))
+            })
+            .collect();
+        pairs.sort_unstable();
+
+        assert!(!expected.is_empty());
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn query_ray_and_frustum_match_brute_force() {
+        let mut prng = Prng::new(Seed(67));
+        let (tree, boxes) = populated(&mut prng);
+
+        let ray = Ray::new(
+            Vector3::new(0.0, -120.0, 0.0),
+            Vector3::new(0.03, 1.0, -0.02).normalized(),
+        );
+        let mut expected: Vec<(usize, f32)> = (0..boxes.len())
+            .filter_map(|i| boxes[i].intersect_ray(&ray).map(|t| (i, t)))
+            .collect();
+        expected.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        let hits: Vec<(usize, f32)> = tree
+            .query_ray(&ray, f32::INFINITY)
+            .into_iter()
+            .map(|(key, t)| (*tree.get(key).unwrap(), t))
+            .collect();
+        assert_eq!(hits, expected);
+
+        let frustum = Frustum::new(&Matrix4([
+            [0.02, 0.0, 0.0, 0.0],
+            [0.0, 0.04, 0.0, -0.2],
+            [0.0, 0.0, 0.025, 0.5],
+            [0.0, 0.0, 0.0, 1.0],
+        ]));
+        let expected: Vec<usize> = (0..boxes.len())
+            .filter(|&i| frustum.contains(&boxes[i]))
+            .collect();
+
+        let values = tree
+            .query_frustum(&frustum)
+            .into_iter()
+            .map(|key| *tree.get(key).unwrap())
+            .collect();
+
+        assert!(!expected.is_empty());
+        assert_eq!(sorted(values), expected);
+    }
+}
@@ -11,6 +11,14 @@ enum FrustumPlaneType {
     Bottom,
 }
 
+/// Position of a volume relative to the frustum
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Intersection {
+    Outside,
+    Intersecting,
+    Inside,
+}
+
 #[derive(Clone, Default, Debug)]
 struct FrustumPlane {
     normal: Vector3,
@@ -20,8 +28,8 @@ struct FrustumPlane {
 impl FrustumPlane {
     fn normalize(&mut self) {
         let length = self.normal.magnitude();
-        self.normal = self.normal * length.recip();
-        self.origin_distance = self.origin_distance / length;
+        self.normal *= length.recip();
+        self.origin_distance /= length;
     }
 }
 
@@ -99,19 +107,25 @@ impl Frustum {
     }
 
     pub fn contains(&self, aabb: &AABB) -> bool {
-        let mut result = true;
+        self.classify(aabb) != Intersection::Outside
+    }
+
+    /// Distinguishes boxes crossing a plane from boxes fully inside, the content of the latter
+    /// does not need to be tested again
+    pub fn classify(&self, aabb: &AABB) -> Intersection {
+        let mut result = Intersection::Inside;
 
         for plane in self.planes.iter() {
             let vp = aabb.vp(&plane.normal);
 
             if Vector3::dot(vp, plane.normal) + plane.origin_distance < 0.0 {
-                return false;
+                return Intersection::Outside;
             }
 
             let vn = aabb.vn(&plane.normal);
 
             if Vector3::dot(vn, plane.normal) + plane.origin_distance < 0.0 {
-                result = true;
+                result = Intersection::Intersecting;
             }
         }
 
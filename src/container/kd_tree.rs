@@ -0,0 +1,350 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::vector::{Vector, Vector2, Vector3};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for crate::vector::Vector2 {}
+
+    impl Sealed for crate::vector::Vector3 {}
+}
+
+/// Point a k-d tree can hold, implemented for Vector2 and Vector3 only
+pub trait KdPoint: Vector + Copy + sealed::Sealed {
+    const DIMENSIONS: usize;
+
+    /// component along the axis, 0 being x
+    fn component(self, axis: usize) -> f32;
+}
+
+impl KdPoint for Vector2 {
+    const DIMENSIONS: usize = 2;
+
+    fn component(self, axis: usize) -> f32 {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => panic!("axis {} out of range for Vector2", axis),
+        }
+    }
+}
+
+impl KdPoint for Vector3 {
+    const DIMENSIONS: usize = 3;
+
+    fn component(self, axis: usize) -> f32 {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            2 => self.z,
+            _ => panic!("axis {} out of range for Vector3", axis),
+        }
+    }
+}
+
+struct Neighbour {
+    distance_squared: f32,
+    index: usize,
+}
+
+impl PartialEq for Neighbour {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Neighbour {}
+
+impl PartialOrd for Neighbour {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbour {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance_squared
+            .partial_cmp(&other.distance_squared)
+            .unwrap_or(Ordering::Equal)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// Bounds of a search, the farthest neighbours found so far are dropped once there are more
+/// than count of them
+struct Search {
+    count: usize,
+    radius_squared: f32,
+    /// squared (1 + epsilon) factor of approximate searches
+    scale: f32,
+    found: BinaryHeap<Neighbour>,
+}
+
+impl Search {
+    fn bound(&self) -> f32 {
+        match self.found.peek() {
+            Some(farthest) if self.found.len() == self.count => {
+                farthest.distance_squared.min(self.radius_squared)
+            }
+            _ => self.radius_squared,
+        }
+    }
+
+    fn into_indices(self) -> Vec<usize> {
+        self.found
+            .into_sorted_vec()
+            .into_iter()
+            .map(|neighbour| neighbour.index)
+            .collect()
+    }
+}
+
+/// K-d tree over a fixed set of 2D or 3D points
+///
+/// The tree is built once from all the points, queries refer to the points by their index in
+/// the slice the tree was built from.
+pub struct KdTree<P> {
+    /// points with their original index, ordered so that each range is split at its middle
+    items: Vec<(P, usize)>,
+    /// axis each range is split along, stored at the position of its middle item
+    axes: Vec<u8>,
+}
+
+impl<P: KdPoint> KdTree<P> {
+    pub fn new(points: &[P]) -> Self {
+        let mut tree = Self {
+            items: points.iter().copied().zip(0..).collect(),
+            axes: vec![0; points.len()],
+        };
+
+        tree.build(0, points.len());
+        tree
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// index of the point closest to the given one
+    pub fn nearest(&self, point: P) -> Option<usize> {
+        self.k_nearest(point, 1).pop()
+    }
+
+    /// indices of the k points closest to the given one, nearest first
+    pub fn k_nearest(&self, point: P, k: usize) -> Vec<usize> {
+        self.run(point, k, f32::INFINITY, 1.0)
+    }
+
+    /// indices of the points at most radius away from the given one, nearest first
+    pub fn within_radius(&self, point: P, radius: f32) -> Vec<usize> {
+        self.run(point, usize::MAX, radius * radius, 1.0)
+    }
+
+    /// index of a point at most (1 + epsilon) times farther than the closest one, visiting
+    /// fewer nodes than an exact search
+    pub fn approximate_nearest(&self, point: P, epsilon: f32) -> Option<usize> {
+        let scale = (1.0 + epsilon) * (1.0 + epsilon);
+        self.run(point, 1, f32::INFINITY, scale).pop()
+    }
+
+    fn run(&self, point: P, count: usize, radius_squared: f32, scale: f32) -> Vec<usize> {
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let mut search = Search {
+            count,
+            radius_squared,
+            scale,
+            found: BinaryHeap::new(),
+        };
+
+        self.search(0, self.items.len(), point, &mut search);
+        search.into_indices()
+    }
+
+    fn build(&mut self, start: usize, end: usize) {
+        if end - start < 2 {
+            return;
+        }
+
+        // split along the axis where the points are the most spread out
+        let axis = (0..P::DIMENSIONS)
+            .map(|axis| {
+                let (min, max) = self.items[start..end].iter().fold(
+                    (f32::INFINITY, f32::NEG_INFINITY),
+                    |(min, max), (point, _)| {
+                        let value = point.component(axis);
+                        (min.min(value), max.max(value))
+                    },
+                );
+                (axis, max - min)
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+            .map_or(0, |(axis, _)| axis);
+
+        let middle = start + (end - start) / 2;
+        self.items[start..end].select_nth_unstable_by(middle - start, |a, b| {
+            a.0.component(axis)
+                .partial_cmp(&b.0.component(axis))
+                .unwrap_or(Ordering::Equal)
+        });
+        self.axes[middle] = axis as u8;
+
+        self.build(start, middle);
+        self.build(middle + 1, end);
+    }
+
+    fn search(&self, start: usize, end: usize, point: P, search: &mut Search) {
+        if start >= end {
+            return;
+        }
+
+        let middle = start + (end - start) / 2;
+        let (split, index) = self.items[middle];
+
+        let distance_squared = (0..P::DIMENSIONS)
+            .map(|axis| {
+                let d = point.component(axis) - split.component(axis);
+                d * d
+            })
+            .sum::<f32>();
+
+        if distance_squared <= search.bound() {
+            search.found.push(Neighbour {
+                distance_squared,
+                index,
+            });
+            if search.found.len() > search.count {
+                search.found.pop();
+            }
+        }
+
+        let axis = self.axes[middle] as usize;
+        let delta = point.component(axis) - split.component(axis);
+        let (near, far) = if delta < 0.0 {
+            ((start, middle), (middle + 1, end))
+        } else {
+            ((middle + 1, end), (start, middle))
+        };
+
+        self.search(near.0, near.1, point, search);
+
+        if delta * delta * search.scale <= search.bound() {
+            self.search(far.0, far.1, point, search);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{Prng, Seed};
+    use crate::vector::{Vector2, Vector3};
+
+    fn random_points2(prng: &mut Prng, count: usize) -> Vec<Vector2> {
+        (0..count)
+            .map(|_| Vector2::new(prng.next_f32() * 100.0, prng.next_f32() * 100.0))
+            .collect()
+    }
+
+    fn random_points3(prng: &mut Prng, count: usize) -> Vec<Vector3> {
+        (0..count)
+            .map(|_| {
+                Vector3::new(
+                    prng.next_f32() * 100.0,
+                    prng.next_f32() * 100.0,
+                    prng.next_f32() * 100.0,
+                )
+            })
+            .collect()
+    }
+
+    fn brute_force<P: KdPoint>(points: &[P], point: P) -> Vec<(f32, usize)> {
+        let mut distances: Vec<(f32, usize)> = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let d = (0..P::DIMENSIONS)
+                    .map(|axis| (p.component(axis) - point.component(axis)).powi(2))
+                    .sum();
+                (d, i)
+            })
+            .collect();
+        distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        distances
+    }
+
+    #[test]
+    fn k_nearest_matches_brute_force() {
+        let mut prng = Prng::new(Seed(101));
+        let points = random_points2(&mut prng, 1000);
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.len(), 1000);
+
+        for _ in 0..50 {
+            let point = Vector2::new(prng.next_f32() * 120.0, prng.next_f32() * 120.0);
+            let expected: Vec<usize> = brute_force(&points, point)
+                .iter()
+                .take(8)
+                .map(|(_, i)| *i)
+                .collect();
+
+            assert_eq!(tree.k_nearest(point, 8), expected);
+            assert_eq!(tree.nearest(point), Some(expected[0]));
+        }
+    }
+
+    #[test]
+    fn within_radius_matches_brute_force() {
+        let mut prng = Prng::new(Seed(103));
+        let points = random_points3(&mut prng, 1000);
+        let tree = KdTree::new(&points);
+
+        for _ in 0..50 {
+            let point = random_points3(&mut prng, 1)[0];
+            let radius = prng.next_f32() * 20.0;
+            let expected: Vec<usize> = brute_force(&points, point)
+                .iter()
+                .take_while(|(d, _)| *d <= radius * radius)
+                .map(|(_, i)| *i)
+                .collect();
+
+            assert_eq!(tree.within_radius(point, radius), expected);
+        }
+    }
+
+    #[test]
+    fn approximate_nearest_is_within_bound() {
+        let mut prng = Prng::new(Seed(107));
+        let points = random_points3(&mut prng, 1000);
+        let tree = KdTree::new(&points);
+
+        for _ in 0..50 {
+            let point = random_points3(&mut prng, 1)[0];
+            let closest = brute_force(&points, point)[0].0.sqrt();
+            let found = points[tree.approximate_nearest(point, 0.5).unwrap()];
+
+            assert!((found - point).magnitude() <= closest * 1.5 + 1e-4);
+        }
+    }
+
+    #[test]
+    fn degenerate_sets() {
+        let empty: KdTree<Vector2> = KdTree::new(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.nearest(Vector2::zero()), None);
+
+        let same = vec![Vector2::identity(); 10];
+        let tree = KdTree::new(&same);
+        assert_eq!(tree.k_nearest(Vector2::zero(), 20).len(), 10);
+        assert_eq!(tree.within_radius(Vector2::identity(), 0.0).len(), 10);
+        assert!(tree.k_nearest(Vector2::zero(), 0).is_empty());
+    }
+}
@@ -1,5 +1,17 @@
 mod aabb;
+mod bvh;
 mod frustum;
+mod kd_tree;
+mod obb;
+mod octree;
+mod quadtree;
+mod spatial_hash;
 
 pub use self::aabb::AABB;
-pub use self::frustum::Frustum;
+pub use self::bvh::Bvh;
+pub use self::frustum::{Frustum, Intersection};
+pub use self::kd_tree::{KdPoint, KdTree};
+pub use self::obb::OBB;
+pub use self::octree::Octree;
+pub use self::quadtree::{Leaves, Quadtree};
+pub use self::spatial_hash::{SpatialHash2, SpatialHash3};
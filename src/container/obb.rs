@@ -0,0 +1,297 @@
+use crate::container::AABB;
+use crate::geometry::Box;
+use crate::matrix::Matrix4;
+use crate::quaternion::Quaternion;
+use crate::transform::Transform;
+use crate::vector::Vector3;
+
+/// Tolerance added to the projected radii so that parallel edges do not produce a degenerate
+/// separating axis
+const EPSILON: f32 = 1e-6;
+
+/// Oriented bounding box
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OBB {
+    pub center: Vector3,
+    /// half of the size along each local axis
+    pub half_extents: Vector3,
+    pub orientation: Quaternion,
+}
+
+impl OBB {
+    pub fn new(center: Vector3, half_extents: Vector3, orientation: Quaternion) -> Self {
+        Self {
+            center,
+            half_extents,
+            orientation,
+        }
+    }
+
+    pub fn from_aabb(aabb: &AABB) -> Self {
+        Self::new(aabb.center(), aabb.half_extents(), Quaternion::identity())
+    }
+
+    /// Places a box expressed in the local space of the transform into world space
+    pub fn from_transform(transform: &Transform, local: &Box) -> Self {
+        let m = transform.matrix();
+        let local = AABB::new(*local);
+
+        let columns = [
+            Vector3::new(m[0][0], m[1][0], m[2][0]),
+            Vector3::new(m[0][1], m[1][1], m[2][1]),
+            Vector3::new(m[0][2], m[1][2], m[2][2]),
+        ];
+        let scale = Vector3::new(
+            columns[0].magnitude(),
+            columns[1].magnitude(),
+            columns[2].magnitude(),
+        );
+
+        let mut rotation = Matrix4::identity();
+        for (i, column) in columns.iter().enumerate() {
+            let axis = column.normalized();
+            rotation[0][i] = axis.x;
+            rotation[1][i] = axis.y;
+            rotation[2][i] = axis.z;
+        }
+
+        let c = local.center();
+        let center = Vector3::new(
+            m[0][0] * c.x + m[0][1] * c.y + m[0][2] * c.z + m[0][3],
+            m[1][0] * c.x + m[1][1] * c.y + m[1][2] * c.z + m[1][3],
+            m[2][0] * c.x + m[2][1] * c.y + m[2][2] * c.z + m[2][3],
+        );
+        let h = local.half_extents();
+
+        Self::new(
+            center,
+            Vector3::new(h.x * scale.x, h.y * scale.y, h.z * scale.z),
+            Quaternion::from_rotation_matrix(&rotation),
+        )
+    }
+
+    /// local x, y and z axes in world space
+    pub fn axes(&self) -> [Vector3; 3] {
+        [
+            self.orientation.rotate(Vector3::right()),
+            self.orientation.rotate(Vector3::up()),
+            self.orientation.rotate(Vector3::forward()),
+        ]
+    }
+
+    pub fn corners(&self) -> [Vector3; 8] {
+        let [ax, ay, az] = self.axes();
+        let (x, y, z) = (
+            ax * self.half_extents.x,
+            ay * self.half_extents.y,
+            az * self.half_extents.z,
+        );
+
+        [
+            self.center - x - y - z,
+            self.center + x - y - z,
+            self.center - x + y - z,
+            self.center + x + y - z,
+            self.center - x - y + z,
+            self.center + x - y + z,
+            self.center - x + y + z,
+            self.center + x + y + z,
+        ]
+    }
+
+    pub fn contains(&self, point: Vector3) -> bool {
+        let d = point - self.center;
+        let axes = self.axes();
+        let extents = [
+            self.half_extents.x,
+            self.half_extents.y,
+            self.half_extents.z,
+        ];
+
+        axes.iter()
+            .zip(extents.iter())
+            .all(|(axis, extent)| Vector3::dot(d, *axis).abs() <= *extent)
+    }
+
+    /// point inside or on the surface of the box closest to the given point
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        let d = point - self.center;
+        let axes = self.axes();
+        let extents = [
+            self.half_extents.x,
+            self.half_extents.y,
+            self.half_extents.z,
+        ];
+
+        axes.iter()
+            .zip(extents.iter())
+            .fold(self.center, |result, (axis, extent)| {
+                let distance = Vector3::dot(d, *axis).max(-extent).min(*extent);
+                result + *axis * distance
+            })
+    }
+
+    /// smallest axis-aligned box enclosing this box
+    pub fn aabb(&self) -> AABB {
+        let [ax, ay, az] = self.axes();
+        let extents = ax.abs() * self.half_extents.x
+            + ay.abs() * self.half_extents.y
+            + az.abs() * self.half_extents.z;
+
+        AABB::from_center(self.center, extents)
+    }
+
+    /// Separating axis test against the 15 candidate axes of two boxes
+    pub fn intersects(&self, other: &OBB) -> bool {
+        let a = self.axes();
+        let b = other.axes();
+        let ea = [
+            self.half_extents.x,
+            self.half_extents.y,
+            self.half_extents.z,
+        ];
+        let eb = [
+            other.half_extents.x,
+            other.half_extents.y,
+            other.half_extents.z,
+        ];
+
+        // rotation expressing b in a's frame
+        let mut r = [[0.0; 3]; 3];
+        let mut abs_r = [[0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                r[i][j] = Vector3::dot(a[i], b[j]);
+                abs_r[i][j] = r[i][j].abs() + EPSILON;
+            }
+        }
+
+        let d = other.center - self.center;
+        let t = [
+            Vector3::dot(d, a[0]),
+            Vector3::dot(d, a[1]),
+            Vector3::dot(d, a[2]),
+        ];
+
+        // a's face normals
+        for i in 0..3 {
+            let rb = eb[0] * abs_r[i][0] + eb[1] * abs_r[i][1] + eb[2] * abs_r[i][2];
+            if t[i].abs() > ea[i] + rb {
+                return false;
+            }
+        }
+
+        // b's face normals
+        for j in 0..3 {
+            let ra = ea[0] * abs_r[0][j] + ea[1] * abs_r[1][j] + ea[2] * abs_r[2][j];
+            let distance = t[0] * r[0][j] + t[1] * r[1][j] + t[2] * r[2][j];
+            if distance.abs() > ra + eb[j] {
+                return false;
+            }
+        }
+
+        // cross products of edge pairs
+        for i in 0..3 {
+            let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+            for j in 0..3 {
+                let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+
+                let ra = ea[i1] * abs_r[i2][j] + ea[i2] * abs_r[i1][j];
+                let rb = eb[j1] * abs_r[i][j2] + eb[j2] * abs_r[i][j1];
+                let distance = t[i2] * r[i1][j] - t[i1] * r[i2][j];
+
+                if distance.abs() > ra + rb {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        self.intersects(&OBB::from_aabb(aabb))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_4;
+
+    fn assert_near(a: Vector3, b: Vector3) {
+        assert!((a - b).magnitude() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn from_transform_places_box_in_world_space() {
+        let transform = Transform::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::zero(),
+            Vector3::new(2.0, 1.0, 1.0),
+        );
+        let local = Box::new(Vector3::new(-0.5, -0.5, -0.5), 1.0, 2.0, 1.0);
+        let obb = OBB::from_transform(&transform, &local);
+
+        assert_near(obb.center, Vector3::new(1.0, 2.5, 3.0));
+        assert_near(obb.half_extents, Vector3::new(1.0, 1.0, 0.5));
+        assert_eq!(obb.orientation, Quaternion::identity());
+    }
+
+    #[test]
+    fn intersects_rotated_boxes() {
+        let a = OBB::new(Vector3::zero(), Vector3::identity(), Quaternion::identity());
+        let rotation = Quaternion::from_axis_angle(Vector3::up(), FRAC_PI_4);
+
+        // the corner of the rotated box reaches sqrt(2) along x
+        let b = OBB::new(Vector3::new(2.3, 0.0, 0.0), Vector3::identity(), rotation);
+        assert!(a.intersects(&b));
+        assert!(b.intersects(&a));
+
+        let b = OBB::new(Vector3::new(2.5, 0.0, 0.0), Vector3::identity(), rotation);
+        assert!(!a.intersects(&b));
+        assert!(!b.intersects(&a));
+    }
+
+    #[test]
+    fn intersects_aabb() {
+        let aabb = AABB::from_min_max(Vector3::zero(), Vector3::new(2.0, 2.0, 2.0));
+        let rotation = Quaternion::from_axis_angle(Vector3::forward(), FRAC_PI_4);
+
+        let obb = OBB::new(Vector3::new(3.3, 1.0, 1.0), Vector3::identity(), rotation);
+        assert!(obb.intersects_aabb(&aabb));
+
+        let obb = OBB::new(Vector3::new(3.5, 1.0, 1.0), Vector3::identity(), rotation);
+        assert!(!obb.intersects_aabb(&aabb));
+    }
+
+    #[test]
+    fn closest_point_clamps_to_surface() {
+        let rotation = Quaternion::from_axis_angle(Vector3::up(), FRAC_PI_4);
+        let obb = OBB::new(Vector3::zero(), Vector3::identity(), rotation);
+
+        let inside = Vector3::new(0.5, 0.5, 0.0);
+        assert_near(obb.closest_point(inside), inside);
+
+        let corner = obb.closest_point(Vector3::new(10.0, 0.0, 0.0));
+        assert_near(corner, Vector3::new(2.0f32.sqrt(), 0.0, 0.0));
+    }
+
+    #[test]
+    fn aabb_encloses_corners() {
+        let rotation = Quaternion::from_axis_angle(Vector3::new(1.0, 1.0, 0.0), 0.7);
+        let obb = OBB::new(
+            Vector3::new(1.0, -2.0, 0.5),
+            Vector3::new(1.0, 2.0, 0.5),
+            rotation,
+        );
+        let aabb = obb.aabb();
+
+        for corner in obb.corners().iter() {
+            let (min, max) = (aabb.min(), aabb.max());
+            assert!(corner.x >= min.x - 1e-4 && corner.x <= max.x + 1e-4);
+            assert!(corner.y >= min.y - 1e-4 && corner.y <= max.y + 1e-4);
+            assert!(corner.z >= min.z - 1e-4 && corner.z <= max.z + 1e-4);
+        }
+    }
+}
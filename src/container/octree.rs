@@ -0,0 +1,551 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::container::{Frustum, Intersection, AABB};
+use crate::geometry::Ray;
+use crate::vector::Vector3;
+
+/// Nodes are enlarged by this factor, so that an object only has to fit in a node by size and
+/// not by position
+const LOOSENESS: f32 = 2.0;
+
+struct Node {
+    center: Vector3,
+    half_size: f32,
+    depth: u32,
+    /// index of the first of the 8 children, which are stored next to each other
+    children: Option<usize>,
+    items: Vec<usize>,
+}
+
+impl Node {
+    fn new(center: Vector3, half_size: f32, depth: u32) -> Self {
+        Self {
+            center,
+            half_size,
+            depth,
+            children: None,
+            items: Vec::new(),
+        }
+    }
+
+    /// bounds that every object stored in this node fits into
+    fn loose_bounds(&self) -> AABB {
+        AABB::from_center(
+            self.center,
+            Vector3::identity() * (self.half_size * LOOSENESS),
+        )
+    }
+
+    fn octant(&self, point: Vector3) -> usize {
+        (point.x >= self.center.x) as usize
+            | ((point.y >= self.center.y) as usize) << 1
+            | ((point.z >= self.center.z) as usize) << 2
+    }
+}
+
+struct Entry<T> {
+    value: T,
+    aabb: AABB,
+    node: usize,
+}
+
+/// Node or entry waiting to be visited by the nearest neighbour search, ordered so that the
+/// closest one comes out of the heap first
+struct Candidate {
+    distance: f32,
+    node: Option<usize>,
+    key: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance
+            .partial_cmp(&self.distance)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Loose octree storing values along with their bounding boxes
+///
+/// Values are referred to by the key returned on insertion, keys of removed values are reused.
+pub struct Octree<T> {
+    nodes: Vec<Node>,
+    entries: Vec<Option<Entry<T>>>,
+    free: Vec<usize>,
+    max_depth: u32,
+}
+
+impl<T> Octree<T> {
+    /// bounds: region where most objects are expected, objects outside of it are kept in the root
+    pub fn new(bounds: &AABB, max_depth: u32) -> Self {
+        let extents = bounds.half_extents();
+        let half_size = extents.x.max(extents.y).max(extents.z);
+
+        Self {
+            nodes: vec![Node::new(bounds.center(), half_size, 0)],
+            entries: Vec::new(),
+            free: Vec::new(),
+            max_depth,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.entry(key).map(|entry| &entry.value)
+    }
+
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.entries
+            .get_mut(key)
+            .and_then(|entry| entry.as_mut())
+            .map(|entry| &mut entry.value)
+    }
+
+    pub fn aabb(&self, key: usize) -> Option<&AABB> {
+        self.entry(key).map(|entry| &entry.aabb)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(key, entry)| entry.as_ref().map(|entry| (key, &entry.value)))
+    }
+
+    pub fn insert(&mut self, value: T, aabb: AABB) -> usize {
+        let node = self.locate(&aabb);
+        let entry = Entry { value, aabb, node };
+
+        let key = match self.free.pop() {
+            Some(key) => {
+                self.entries[key] = Some(entry);
+                key
+            }
+            None => {
+                self.entries.push(Some(entry));
+                self.entries.len() - 1
+            }
+        };
+
+        self.nodes[node].items.push(key);
+        key
+    }
+
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        let entry = self.entries.get_mut(key)?.take()?;
+        self.detach(entry.node, key);
+        self.free.push(key);
+
+        Some(entry.value)
+    }
+
+    /// Moves a value to its new bounds, returns false if the key is unknown
+    pub fn update(&mut self, key: usize, aabb: AABB) -> bool {
+        let current = match self.entry(key) {
+            Some(entry) => entry.node,
+            None => return false,
+        };
+
+        let node = self.locate(&aabb);
+        if node != current {
+            self.detach(current, key);
+            self.nodes[node].items.push(key);
+        }
+
+        if let Some(entry) = self.entries[key].as_mut() {
+            entry.aabb = aabb;
+            entry.node = node;
+        }
+
+        true
+    }
+
+    /// keys of the values whose bounds intersect the region
+    pub fn query_aabb(&self, region: &AABB) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+
+            if index != 0 && !node.loose_bounds().intersects(region) {
+                continue;
+            }
+
+            for &key in node.items.iter() {
+                if self.entry_aabb(key).intersects(region) {
+                    result.push(key);
+                }
+            }
+
+            if let Some(first) = node.children {
+                stack.extend(first..first + 8);
+            }
+        }
+
+        result
+    }
+
+    /// keys of the values hit by the ray along with the hit distance, closest first
+    pub fn query_ray(&self, ray: &Ray, max_distance: f32) -> Vec<(usize, f32)> {
+        let mut result = Vec::new();
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+
+            if index != 0 {
+                match node.loose_bounds().intersect_ray(ray) {
+                    Some(t) if t <= max_distance => {}
+                    _ => continue,
+                }
+            }
+
+            for &key in node.items.iter() {
+                if let Some(t) = self.entry_aabb(key).intersect_ray(ray) {
+                    if t <= max_distance {
+                        result.push((key, t));
+                    }
+                }
+            }
+
+            if let Some(first) = node.children {
+                stack.extend(first..first + 8);
+            }
+        }
+
+        result.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        result
+    }
+
+    /// key of the value whose bounds are the closest to the point
+    pub fn nearest(&self, point: Vector3) -> Option<usize> {
+        let mut heap = BinaryHeap::new();
+        heap.push(Candidate {
+            distance: 0.0,
+            node: Some(0),
+            key: 0,
+        });
+
+        while let Some(candidate) = heap.pop() {
+            let index = match candidate.node {
+                Some(index) => index,
+                // entries only come out once every node that could hold a closer one is visited
+                None => return Some(candidate.key),
+            };
+
+            let node = &self.nodes[index];
+
+            for &key in node.items.iter() {
+                heap.push(Candidate {
+                    distance: self.entry_aabb(key).distance_squared(point),
+                    node: None,
+                    key,
+                });
+            }
+
+            if let Some(first) = node.children {
+                for child in first..first + 8 {
+                    heap.push(Candidate {
+                        distance: self.nodes[child].loose_bounds().distance_squared(point),
+                        node: Some(child),
+                        key: 0,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// keys of the values visible in the frustum
+    pub fn query_frustum(&self, frustum: &Frustum) -> Vec<usize> {
+        let mut result = Vec::new();
+        self.query_frustum_node(0, frustum, &mut result);
+        result
+    }
+
+    fn query_frustum_node(&self, index: usize, frustum: &Frustum, result: &mut Vec<usize>) {
+        let node = &self.nodes[index];
+
+        // objects outside of the root bounds are kept in the root, it cannot be culled
+        let intersection = if index == 0 {
+            Intersection::Intersecting
+        } else {
+            frustum.classify(&node.loose_bounds())
+        };
+
+        match intersection {
+            Intersection::Outside => {}
+            Intersection::Inside => self.collect(index, result),
+            Intersection::Intersecting => {
+                for &key in node.items.iter() {
+                    if frustum.contains(self.entry_aabb(key)) {
+                        result.push(key);
+                    }
+                }
+
+                if let Some(first) = node.children {
+                    for child in first..first + 8 {
+                        self.query_frustum_node(child, frustum, result);
+                    }
+                }
+            }
+        }
+    }
+
+    /// every key stored in the subtree
+    fn collect(&self, index: usize, result: &mut Vec<usize>) {
+        let mut stack = vec![index];
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            result.extend(node.items.iter());
+
+            if let Some(first) = node.children {
+                stack.extend(first..first + 8);
+            }
+        }
+    }
+
+    /// deepest node whose loose bounds can hold the box, creating it if needed
+    fn locate(&mut self, aabb: &AABB) -> usize {
+        let extents = aabb.half_extents();
+        let radius = extents.x.max(extents.y).max(extents.z);
+        let center = aabb.center();
+        let mut index = 0;
+
+        loop {
+            let node = &self.nodes[index];
+            let child_half_size = node.half_size * 0.5;
+            let offset = (center - node.center).abs();
+            let inside = offset.x <= node.half_size
+                && offset.y <= node.half_size
+                && offset.z <= node.half_size;
+
+            if node.depth >= self.max_depth || radius > child_half_size || !inside {
+                return index;
+            }
+
+            let first = match node.children {
+                Some(first) => first,
+                None => self.split(index),
+            };
+
+            index = first + self.nodes[index].octant(center);
+        }
+    }
+
+    fn split(&mut self, index: usize) -> usize {
+        let (center, half_size, depth) = {
+            let node = &self.nodes[index];
+            (node.center, node.half_size * 0.5, node.depth + 1)
+        };
+        let first = self.nodes.len();
+
+        for octant in 0..8 {
+            let offset = Vector3::new(
+                if octant & 1 != 0 {
+                    half_size
+                } else {
+                    -half_size
+                },
+                if octant & 2 != 0 {
+                    half_size
+                } else {
+                    -half_size
+                },
+                if octant & 4 != 0 {
+                    half_size
+                } else {
+                    -half_size
+                },
+            );
+            self.nodes
+                .push(Node::new(center + offset, half_size, depth));
+        }
+
+        self.nodes[index].children = Some(first);
+        first
+    }
+
+    fn detach(&mut self, node: usize, key: usize) {
+        let items = &mut self.nodes[node].items;
+        if let Some(position) = items.iter().position(|&item| item == key) {
+            items.swap_remove(position);
+        }
+    }
+
+    fn entry(&self, key: usize) -> Option<&Entry<T>> {
+        self.entries.get(key).and_then(|entry| entry.as_ref())
+    }
+
+    fn entry_aabb(&self, key: usize) -> &AABB {
+        &self.entries[key].as_ref().unwrap().aabb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::Matrix4;
+    use crate::random::{Prng, Seed};
+
+    fn random_aabb(prng: &mut Prng) -> AABB {
+        let center = Vector3::new(
+            prng.next_f32() * 200.0 - 100.0,
+            prng.next_f32() * 200.0 - 100.0,
+            prng.next_f32() * 200.0 - 100.0,
+        );
+        let size = prng.next_f32() * 8.0 + 0.1;
+        AABB::from_center(center, Vector3::identity() * size)
+    }
+
+    fn populated(prng: &mut Prng) -> (Octree<usize>, Vec<AABB>) {
+        let bounds = AABB::from_center(Vector3::zero(), Vector3::identity() * 100.0);
+        let mut tree = Octree::new(&bounds, 6);
+        let mut boxes = Vec::new();
+
+        for i in 0..500 {
+            let aabb = random_aabb(prng);
+            assert_eq!(tree.insert(i, aabb), i);
+            boxes.push(aabb);
+        }
+
+        (tree, boxes)
+    }
+
+    /// maps the region to the clip space cube
+    fn orthographic(min: Vector3, max: Vector3) -> Matrix4 {
+        let size = max - min;
+        Matrix4([
+            [2.0 / size.x, 0.0, 0.0, -(max.x + min.x) / size.x],
+            [0.0, 2.0 / size.y, 0.0, -(max.y + min.y) / size.y],
+            [0.0, 0.0, 2.0 / size.z, -(max.z + min.z) / size.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    fn sorted(mut keys: Vec<usize>) -> Vec<usize> {
+        keys.sort_unstable();
+        keys
+    }
+
+    #[test]
+    fn query_aabb_matches_brute_force() {
+        let mut prng = Prng::new(Seed(29));
+        let (tree, boxes) = populated(&mut prng);
+
+        for _ in 0..50 {
+            let region = random_aabb(&mut prng);
+            let expected: Vec<usize> = (0..boxes.len())
+                .filter(|&i| boxes[i].intersects(&region))
+                .collect();
+
+            assert_eq!(sorted(tree.query_aabb(&region)), expected);
+        }
+    }
+
+    #[test]
+    fn remove_and_update() {
+        let mut prng = Prng::new(Seed(31));
+        let (mut tree, mut boxes) = populated(&mut prng);
+
+        for i in 0..100 {
+            assert_eq!(tree.remove(i), Some(i));
+        }
+        assert_eq!(tree.remove(0), None);
+        assert_eq!(tree.len(), 400);
+
+        for (i, aabb) in boxes.iter_mut().enumerate().take(300).skip(100) {
+            *aabb = random_aabb(&mut prng);
+            assert!(tree.update(i, *aabb));
+        }
+
+        let region = AABB::from_center(Vector3::zero(), Vector3::identity() * 60.0);
+        let expected: Vec<usize> = (100..boxes.len())
+            .filter(|&i| boxes[i].intersects(&region))
+            .collect();
+        assert_eq!(sorted(tree.query_aabb(&region)), expected);
+
+        // freed keys are reused
+        let key = tree.insert(1000, boxes[0]);
+        assert!(key < 100);
+        assert_eq!(tree.get(key), Some(&1000));
+    }
+
+    #[test]
+    fn query_ray_matches_brute_force() {
+        let mut prng = Prng::new(Seed(37));
+        let (tree, boxes) = populated(&mut prng);
+
+        let ray = Ray::new(
+            Vector3::new(-120.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.05, 0.02).normalized(),
+        );
+        let mut expected: Vec<(usize, f32)> = (0..boxes.len())
+            .filter_map(|i| boxes[i].intersect_ray(&ray).map(|t| (i, t)))
+            .filter(|(_, t)| *t <= 150.0)
+            .collect();
+        expected.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+        assert_eq!(tree.query_ray(&ray, 150.0), expected);
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let mut prng = Prng::new(Seed(41));
+        let (tree, boxes) = populated(&mut prng);
+
+        for _ in 0..50 {
+            let point = random_aabb(&mut prng).center();
+            let key = tree.nearest(point).unwrap();
+            let best = boxes
+                .iter()
+                .map(|aabb| aabb.distance_squared(point))
+                .fold(f32::INFINITY, f32::min);
+
+            assert_eq!(boxes[key].distance_squared(point), best);
+        }
+    }
+
+    #[test]
+    fn query_frustum_matches_brute_force() {
+        let mut prng = Prng::new(Seed(43));
+        let (tree, boxes) = populated(&mut prng);
+
+        let frustum = Frustum::new(&orthographic(
+            Vector3::new(-50.0, -20.0, -80.0),
+            Vector3::new(30.0, 70.0, 10.0),
+        ));
+        let expected: Vec<usize> = (0..boxes.len())
+            .filter(|&i| frustum.contains(&boxes[i]))
+            .collect();
+
+        assert!(!expected.is_empty());
+        assert_eq!(sorted(tree.query_frustum(&frustum)), expected);
+    }
+}
@@ -0,0 +1,439 @@
+use crate::geometry::Rect;
+use crate::vector::Vector2;
+
+struct Node {
+    bounds: Rect,
+    depth: u32,
+    parent: Option<usize>,
+    /// index of the first of the 4 children, which are stored next to each other
+    children: Option<usize>,
+    items: Vec<usize>,
+}
+
+struct Entry<T> {
+    value: T,
+    bounds: Rect,
+    node: usize,
+}
+
+/// Quadtree storing values along with their bounding rectangles
+///
+/// A value is kept in the deepest node that fully encloses it, nodes are split once they hold
+/// more than capacity values and merged back when their children become sparse. Values are
+/// referred to by the key returned on insertion, keys of removed values are reused.
+pub struct Quadtree<T> {
+    nodes: Vec<Node>,
+    /// first index of the blocks of 4 nodes that can be reused
+    free_nodes: Vec<usize>,
+    entries: Vec<Option<Entry<T>>>,
+    free: Vec<usize>,
+    capacity: usize,
+    max_depth: u32,
+}
+
+impl<T> Quadtree<T> {
+    /// bounds: region where most values are expected, values outside of it are kept in the root
+    pub fn new(bounds: Rect, capacity: usize, max_depth: u32) -> Self {
+        let min = bounds.min();
+        let size = bounds.max() - min;
+        let root = Node {
+            bounds: Rect::new(min, size.x, size.y),
+            depth: 0,
+            parent: None,
+            children: None,
+            items: Vec::new(),
+        };
+
+        Self {
+            nodes: vec![root],
+            free_nodes: Vec::new(),
+            entries: Vec::new(),
+            free: Vec::new(),
+            capacity,
+            max_depth,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.entry(key).map(|entry| &entry.value)
+    }
+
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.entries
+            .get_mut(key)
+            .and_then(|entry| entry.as_mut())
+            .map(|entry| &mut entry.value)
+    }
+
+    pub fn bounds(&self, key: usize) -> Option<&Rect> {
+        self.entry(key).map(|entry| &entry.bounds)
+    }
+
+    pub fn insert(&mut self, value: T, bounds: Rect) -> usize {
+        let entry = Entry {
+            value,
+            bounds,
+            node: 0,
+        };
+
+        let key = match self.free.pop() {
+            Some(key) => {
+                self.entries[key] = Some(entry);
+                key
+            }
+            None => {
+                self.entries.push(Some(entry));
+                self.entries.len() - 1
+            }
+        };
+
+        let node = self.locate(0, &bounds);
+        self.attach(node, key);
+
+        if self.nodes[node].children.is_none()
+            && self.nodes[node].items.len() > self.capacity
+            && self.nodes[node].depth < self.max_depth
+        {
+            self.split(node);
+        }
+
+        key
+    }
+
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        let entry = self.entries.get_mut(key)?.take()?;
+        let items = &mut self.nodes[entry.node].items;
+        if let Some(position) = items.iter().position(|&item| item == key) {
+            items.swap_remove(position);
+        }
+        self.free.push(key);
+
+        let mut node = entry.node;
+        if self.nodes[node].children.is_none() {
+            node = self.nodes[node].parent.unwrap_or(node);
+        }
+        self.merge(node);
+
+        Some(entry.value)
+    }
+
+    /// keys of the values whose bounds contain the point
+    pub fn query_point(&self, point: Vector2) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut stack = vec![0];
+
+        // a point on a split line is in several children, each may hold values containing it
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+
+            result.extend(
+                node.items
+                    .iter()
+                    .filter(|&&key| self.entry_bounds(key).contains(point)),
+            );
+
+            if let Some(first) = node.children {
+                stack.extend(
+                    (first..first + 4).filter(|&child| self.nodes[child].bounds.contains(point)),
+                );
+            }
+        }
+
+        result
+    }
+
+    /// keys of the values whose bounds intersect the region
+    pub fn query_rect(&self, region: &Rect) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+
+            if index != 0 && !node.bounds.intersects(*region) {
+                continue;
+            }
+
+            result.extend(
+                node.items
+                    .iter()
+                    .filter(|&&key| self.entry_bounds(key).intersects(*region)),
+            );
+
+            if let Some(first) = node.children {
+                stack.extend(first..first + 4);
+            }
+        }
+
+        result
+    }
+
+    /// Bounds and depth of every leaf node, to visualize the subdivision
+    pub fn leaves(&self) -> Leaves<'_> {
+        Leaves {
+            nodes: &self.nodes,
+            stack: vec![0],
+        }
+    }
+
+    /// deepest existing node under index enclosing the bounds
+    fn locate(&self, mut index: usize, bounds: &Rect) -> usize {
+        while let Some(first) = self.nodes[index].children {
+            match (first..first + 4).find(|&child| self.nodes[child].bounds.encloses(bounds)) {
+                Some(child) => index = child,
+                None => break,
+            }
+        }
+
+        index
+    }
+
+    fn attach(&mut self, node: usize, key: usize) {
+        self.nodes[node].items.push(key);
+        self.entries[key].as_mut().unwrap().node = node;
+    }
+
+    fn split(&mut self, index: usize) {
+        let (bounds, depth) = (self.nodes[index].bounds, self.nodes[index].depth + 1);
+        let half = bounds.size * 0.5;
+        let quadrants = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(half.x, 0.0),
+            Vector2::new(0.0, half.y),
+            Vector2::new(half.x, half.y),
+        ];
+
+        let first = match self.free_nodes.pop() {
+            Some(first) => first,
+            None => {
+                self.nodes.extend((0..4).map(|_| Node {
+                    bounds,
+                    depth,
+                    parent: None,
+                    children: None,
+                    items: Vec::new(),
+                }));
+                self.nodes.len() - 4
+            }
+        };
+
+        for (i, offset) in quadrants.iter().enumerate() {
+            let node = &mut self.nodes[first + i];
+            node.bounds = Rect::new(bounds.origin + *offset, half.x, half.y);
+            node.depth = depth;
+            node.parent = Some(index);
+            node.children = None;
+        }
+
+        self.nodes[index].children = Some(first);
+
+        // push down the values that fit in a single child
+        let items = std::mem::take(&mut self.nodes[index].items);
+        for key in items {
+            let node = self.locate(index, &self.entry_bounds(key));
+            self.attach(node, key);
+        }
+
+        for child in first..first + 4 {
+            if self.nodes[child].items.len() > self.capacity && depth < self.max_depth {
+                self.split(child);
+            }
+        }
+    }
+
+    /// collapses the children of index, and then of its ancestors, while they hold few values
+    fn merge(&mut self, mut index: usize) {
+        loop {
+            let first = match self.nodes[index].children {
+                Some(first) => first,
+                None => return,
+            };
+
+            let children = first..first + 4;
+            if children
+                .clone()
+                .any(|child| self.nodes[child].children.is_some())
+            {
+                return;
+            }
+
+            let count: usize = self.nodes[index].items.len()
+                + children
+                    .clone()
+                    .map(|child| self.nodes[child].items.len())
+                    .sum::<usize>();
+            if count > self.capacity {
+                return;
+            }
+
+            for child in children {
+                let items = std::mem::take(&mut self.nodes[child].items);
+                for key in items {
+                    self.attach(index, key);
+                }
+            }
+
+            self.nodes[index].children = None;
+            self.free_nodes.push(first);
+
+            match self.nodes[index].parent {
+                Some(parent) => index = parent,
+                None => return,
+            }
+        }
+    }
+
+    fn entry(&self, key: usize) -> Option<&Entry<T>> {
+        self.entries.get(key).and_then(|entry| entry.as_ref())
+    }
+
+    fn entry_bounds(&self, key: usize) -> Rect {
+        self.entries[key].as_ref().unwrap().bounds
+    }
+}
+
+/// Iterator over the leaves of a quadtree
+pub struct Leaves<'a> {
+    nodes: &'a [Node],
+    stack: Vec<usize>,
+}
+
+impl<'a> Iterator for Leaves<'a> {
+    type Item = (Rect, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(index) = self.stack.pop() {
+            let node = &self.nodes[index];
+
+            match node.children {
+                Some(first) => self.stack.extend((first..first + 4).rev()),
+                None => return Some((node.bounds, node.depth)),
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{Prng, Seed};
+
+    fn random_rect(prng: &mut Prng) -> Rect {
+        Rect::new(
+            Vector2::new(prng.next_f32() * 200.0, prng.next_f32() * 200.0),
+            prng.next_f32() * 6.0,
+            prng.next_f32() * 6.0,
+        )
+    }
+
+    fn sorted(mut keys: Vec<usize>) -> Vec<usize> {
+        keys.sort_unstable();
+        keys
+    }
+
+    #[test]
+    fn point_on_a_split_line() {
+        let mut tree = Quadtree::new(Rect::square(Vector2::zero(), 100.0), 1, 4);
+        let left = tree.insert("left", Rect::new(Vector2::new(40.0, 10.0), 10.0, 10.0));
+        let right = tree.insert("right", Rect::new(Vector2::new(50.0, 10.0), 10.0, 10.0));
+        assert!(tree.leaves().count() > 1);
+
+        assert_eq!(
+            sorted(tree.query_point(Vector2::new(50.0, 15.0))),
+            vec![left, right]
+        );
+        assert_eq!(tree.query_point(Vector2::new(45.0, 15.0)), vec![left]);
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let mut prng = Prng::new(Seed(83));
+        let bounds = Rect::square(Vector2::zero(), 200.0);
+        let mut tree = Quadtree::new(bounds, 4, 8);
+        let mut rects = Vec::new();
+
+        for i in 0..800 {
+            let rect = random_rect(&mut prng);
+            assert_eq!(tree.insert(i, rect), i);
+            rects.push(rect);
+        }
+
+        for _ in 0..50 {
+            let point = Vector2::new(prng.next_f32() * 200.0, prng.next_f32() * 200.0);
+            let expected: Vec<usize> = (0..rects.len())
+                .filter(|&i| rects[i].contains(point))
+                .collect();
+            assert_eq!(sorted(tree.query_point(point)), expected);
+
+            let region = Rect::square(point, prng.next_f32() * 40.0);
+            let expected: Vec<usize> = (0..rects.len())
+                .filter(|&i| rects[i].intersects(region))
+                .collect();
+            assert_eq!(sorted(tree.query_rect(&region)), expected);
+        }
+    }
+
+    #[test]
+    fn leaves_cover_the_bounds() {
+        let mut prng = Prng::new(Seed(89));
+        let bounds = Rect::square(Vector2::zero(), 200.0);
+        let mut tree = Quadtree::new(bounds, 2, 6);
+
+        for i in 0..200 {
+            tree.insert(i, random_rect(&mut prng));
+        }
+
+        let leaves: Vec<(Rect, u32)> = tree.leaves().collect();
+        let area: f32 = leaves
+            .iter()
+            .map(|(rect, _)| rect.size.x * rect.size.y)
+            .sum();
+
+        assert!(leaves.len() > 4);
+        assert!(leaves.iter().all(|(_, depth)| *depth <= 6));
+        assert!((area - 200.0 * 200.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn removal_merges_nodes() {
+        let mut prng = Prng::new(Seed(97));
+        let bounds = Rect::square(Vector2::zero(), 200.0);
+        let mut tree = Quadtree::new(bounds, 4, 8);
+        let mut rects = Vec::new();
+
+        for i in 0..300 {
+            let rect = random_rect(&mut prng);
+            tree.insert(i, rect);
+            rects.push(rect);
+        }
+
+        let leaves = tree.leaves().count();
+
+        for i in 0..290 {
+            assert_eq!(tree.remove(i), Some(i));
+        }
+        assert_eq!(tree.remove(0), None);
+        assert_eq!(tree.len(), 10);
+        assert!(tree.leaves().count() * 8 < leaves);
+
+        let expected: Vec<usize> = (290..300).collect();
+        assert_eq!(sorted(tree.query_rect(&bounds)), expected);
+
+        // freed nodes and keys are reused
+        for (i, rect) in rects.iter().enumerate() {
+            tree.insert(1000 + i, *rect);
+        }
+        assert_eq!(tree.len(), 310);
+        assert_eq!(tree.query_rect(&bounds).len(), 310);
+    }
+}
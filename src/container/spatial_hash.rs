@@ -0,0 +1,536 @@
+use std::collections::HashMap;
+
+use crate::container::AABB;
+use crate::geometry::{Box, Rect};
+use crate::vector::{Vector2, Vector3};
+
+struct Entry<T, const N: usize> {
+    value: T,
+    min: [f32; N],
+    max: [f32; N],
+    /// inclusive range of the cells covered by the bounds
+    cells: ([i32; N], [i32; N]),
+}
+
+/// entries covering more cells than this are kept aside instead of being linked to each cell
+const MAX_LINKED_CELLS: f64 = 256.0;
+
+/// Dimension independent part of the spatial hashes
+struct Grid<T, const N: usize> {
+    cell_size: f32,
+    cells: HashMap<[i32; N], Vec<usize>>,
+    /// entries too large to be linked to their cells, checked by every query
+    large: Vec<usize>,
+    entries: Vec<Option<Entry<T, N>>>,
+    free: Vec<usize>,
+}
+
+impl<T, const N: usize> Grid<T, N> {
+    fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "The cell size has to be positive");
+
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            large: Vec::new(),
+            entries: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len() - self.free.len()
+    }
+
+    fn entry(&self, key: usize) -> Option<&Entry<T, N>> {
+        self.entries.get(key).and_then(|entry| entry.as_ref())
+    }
+
+    fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.entries
+            .get_mut(key)
+            .and_then(|entry| entry.as_mut())
+            .map(|entry| &mut entry.value)
+    }
+
+    fn cell_range(&self, min: &[f32; N], max: &[f32; N]) -> ([i32; N], [i32; N]) {
+        let cell = |value: f32| (value / self.cell_size).floor() as i32;
+        (min.map(cell), max.map(cell))
+    }
+
+    fn insert(&mut self, value: T, min: [f32; N], max: [f32; N]) -> usize {
+        let cells = self.cell_range(&min, &max);
+        let entry = Entry {
+            value,
+            min,
+            max,
+            cells,
+        };
+
+        let key = match self.free.pop() {
+            Some(key) => {
+                self.entries[key] = Some(entry);
+                key
+            }
+            None => {
+                self.entries.push(Some(entry));
+                self.entries.len() - 1
+            }
+        };
+
+        self.link(key, cells);
+        key
+    }
+
+    fn remove(&mut self, key: usize) -> Option<T> {
+        let entry = self.entries.get_mut(key)?.take()?;
+        self.unlink(key, entry.cells);
+        self.free.push(key);
+
+        Some(entry.value)
+    }
+
+    fn update(&mut self, key: usize, min: [f32; N], max: [f32; N]) -> bool {
+        let cells = self.cell_range(&min, &max);
+        let previous = match self.entries.get_mut(key).and_then(|entry| entry.as_mut()) {
+            Some(entry) => {
+                let previous = entry.cells;
+                entry.min = min;
+                entry.max = max;
+                entry.cells = cells;
+                previous
+            }
+            None => return false,
+        };
+
+        // most moves stay within the same cells
+        if previous != cells {
+            self.unlink(key, previous);
+            self.link(key, cells);
+        }
+
+        true
+    }
+
+    /// keys of the entries accepted by the filter among those sharing a cell with the region
+    fn query<F>(&self, min: &[f32; N], max: &[f32; N], filter: F) -> Vec<usize>
+    where
+        F: Fn(&Entry<T, N>) -> bool,
+    {
+        let range = self.cell_range(min, max);
+
+        // past a point, walking the cells costs more than checking every entry
+        if cell_count(range) > self.len() as f64 {
+            return self
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| matches!(entry, Some(entry) if filter(entry)))
+                .map(|(key, _)| key)
+                .collect();
+        }
+
+        let mut result: Vec<usize> = self
+            .large
+            .iter()
+            .copied()
+            .filter(|&key| filter(self.entries[key].as_ref().unwrap()))
+            .collect();
+
+        for_each_cell(range, |cell| {
+            if let Some(keys) = self.cells.get(&cell) {
+                result.extend(
+                    keys.iter()
+                        .filter(|&&key| filter(self.entries[key].as_ref().unwrap())),
+                );
+            }
+        });
+
+        // entries spanning several cells are found once per cell
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+
+    fn query_region(&self, min: [f32; N], max: [f32; N]) -> Vec<usize> {
+        self.query(&min, &max, |entry| {
+            (0..N).all(|i| entry.min[i] <= max[i] && min[i] <= entry.max[i])
+        })
+    }
+
+    fn query_radius(&self, center: [f32; N], radius: f32) -> Vec<usize> {
+        let min = center.map(|value| value - radius);
+        let max = center.map(|value| value + radius);
+
+        self.query(&min, &max, |entry| {
+            let distance_squared: f32 = (0..N)
+                .map(|i| {
+                    let closest = center[i].max(entry.min[i]).min(entry.max[i]);
+                    (center[i] - closest).powi(2)
+                })
+                .sum();
+
+            distance_squared <= radius * radius
+        })
+    }
+
+    fn link(&mut self, key: usize, range: ([i32; N], [i32; N])) {
+        if cell_count(range) > MAX_LINKED_CELLS {
+            self.large.push(key);
+            return;
+        }
+
+        let cells = &mut self.cells;
+        for_each_cell(range, |cell| cells.entry(cell).or_default().push(key));
+    }
+
+    fn unlink(&mut self, key: usize, range: ([i32; N], [i32; N])) {
+        if cell_count(range) > MAX_LINKED_CELLS {
+            if let Some(position) = self.large.iter().position(|&item| item == key) {
+                self.large.swap_remove(position);
+            }
+            return;
+        }
+
+        let cells = &mut self.cells;
+        for_each_cell(range, |cell| {
+            if let Some(keys) = cells.get_mut(&cell) {
+                if let Some(position) = keys.iter().position(|&item| item == key) {
+                    keys.swap_remove(position);
+                }
+
+                if keys.is_empty() {
+                    cells.remove(&cell);
+                }
+            }
+        });
+    }
+}
+
+/// number of cells in the inclusive range, as a float since it can overflow any integer
+fn cell_count<const N: usize>((min, max): ([i32; N], [i32; N])) -> f64 {
+    (0..N).fold(1.0, |count, axis| {
+        count * (max[axis] as f64 - min[axis] as f64 + 1.0).max(0.0)
+    })
+}
+
+/// visits every cell of the inclusive range
+fn for_each_cell<F, const N: usize>((min, max): ([i32; N], [i32; N]), mut f: F)
+where
+    F: FnMut([i32; N]),
+{
+    if (0..N).any(|i| min[i] > max[i]) {
+        return;
+    }
+
+    let mut cell = min;
+
+    loop {
+        f(cell);
+
+        let mut axis = 0;
+        loop {
+            if axis == N {
+                return;
+            }
+
+            if cell[axis] < max[axis] {
+                cell[axis] += 1;
+                break;
+            }
+
+            cell[axis] = min[axis];
+            axis += 1;
+        }
+    }
+}
+
+fn rect_bounds(rect: &Rect) -> ([f32; 2], [f32; 2]) {
+    let (a, b) = (rect.origin, rect.origin + rect.size);
+    ([a.x.min(b.x), a.y.min(b.y)], [a.x.max(b.x), a.y.max(b.y)])
+}
+
+fn aabb_bounds(aabb: &AABB) -> ([f32; 3], [f32; 3]) {
+    let (min, max) = (aabb.min(), aabb.max());
+    ([min.x, min.y, min.z], [max.x, max.y, max.z])
+}
+
+/// Hash grid indexing points or rectangles in the plane by the cells they cover
+///
+/// Values are referred to by the key returned on insertion, keys of removed values are reused.
+pub struct SpatialHash2<T> {
+    grid: Grid<T, 2>,
+}
+
+impl<T> SpatialHash2<T> {
+    /// cell_size: should be close to the typical query radius
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            grid: Grid::new(cell_size),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.grid.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.grid.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.grid.entry(key).map(|entry| &entry.value)
+    }
+
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.grid.get_mut(key)
+    }
+
+    pub fn insert(&mut self, value: T, bounds: &Rect) -> usize {
+        let (min, max) = rect_bounds(bounds);
+        self.grid.insert(value, min, max)
+    }
+
+    pub fn insert_point(&mut self, value: T, point: Vector2) -> usize {
+        self.grid
+            .insert(value, [point.x, point.y], [point.x, point.y])
+    }
+
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        self.grid.remove(key)
+    }
+
+    /// Moves a value to its new bounds, returns false if the key is unknown
+    pub fn update(&mut self, key: usize, bounds: &Rect) -> bool {
+        let (min, max) = rect_bounds(bounds);
+        self.grid.update(key, min, max)
+    }
+
+    /// Moves a value to its new position, returns false if the key is unknown
+    pub fn move_point(&mut self, key: usize, point: Vector2) -> bool {
+        self.grid
+            .update(key, [point.x, point.y], [point.x, point.y])
+    }
+
+    /// keys of the values within radius of the center, in ascending order
+    pub fn query_radius(&self, center: Vector2, radius: f32) -> Vec<usize> {
+        self.grid.query_radius([center.x, center.y], radius)
+    }
+
+    /// keys of the values intersecting the region, in ascending order
+    pub fn query_rect(&self, region: &Rect) -> Vec<usize> {
+        let (min, max) = rect_bounds(region);
+        self.grid.query_region(min, max)
+    }
+}
+
+/// Hash grid indexing points or boxes in space by the cells they cover
+///
+/// Values are referred to by the key returned on insertion, keys of removed values are reused.
+pub struct SpatialHash3<T> {
+    grid: Grid<T, 3>,
+}
+
+impl<T> SpatialHash3<T> {
+    /// cell_size: should be close to the typical query radius
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            grid: Grid::new(cell_size),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.grid.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.grid.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, key: usize) -> Option<&T> {
+        self.grid.entry(key).map(|entry| &entry.value)
+    }
+
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.grid.get_mut(key)
+    }
+
+    pub fn insert(&mut self, value: T, bounds: &AABB) -> usize {
+        let (min, max) = aabb_bounds(bounds);
+        self.grid.insert(value, min, max)
+    }
+
+    pub fn insert_point(&mut self, value: T, point: Vector3) -> usize {
+        let point = [point.x, point.y, point.z];
+        self.grid.insert(value, point, point)
+    }
+
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        self.grid.remove(key)
+    }
+
+    /// Moves a value to its new bounds, returns false if the key is unknown
+    pub fn update(&mut self, key: usize, bounds: &AABB) -> bool {
+        let (min, max) = aabb_bounds(bounds);
+        self.grid.update(key, min, max)
+    }
+
+    /// Moves a value to its new position, returns false if the key is unknown
+    pub fn move_point(&mut self, key: usize, point: Vector3) -> bool {
+        let point = [point.x, point.y, point.z];
+        self.grid.update(key, point, point)
+    }
+
+    /// keys of the values within radius of the center, in ascending order
+    pub fn query_radius(&self, center: Vector3, radius: f32) -> Vec<usize> {
+        self.grid
+            .query_radius([center.x, center.y, center.z], radius)
+    }
+
+    /// keys of the values intersecting the region, in ascending order
+    pub fn query_box(&self, region: &Box) -> Vec<usize> {
+        let (min, max) = aabb_bounds(&AABB::new(*region));
+        self.grid.query_region(min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{Prng, Seed};
+
+    fn random_vector3(prng: &mut Prng) -> Vector3 {
+        Vector3::new(
+            prng.next_f32() * 100.0 - 50.0,
+            prng.next_f32() * 100.0 - 50.0,
+            prng.next_f32() * 100.0 - 50.0,
+        )
+    }
+
+    fn random_vector2(prng: &mut Prng) -> Vector2 {
+        Vector2::new(
+            prng.next_f32() * 100.0 - 50.0,
+            prng.next_f32() * 100.0 - 50.0,
+        )
+    }
+
+    #[test]
+    fn query_radius_matches_brute_force() {
+        let mut prng = Prng::new(Seed(71));
+        let mut hash = SpatialHash3::new(4.0);
+        let mut points = Vec::new();
+
+        for i in 0..1000 {
+            let point = random_vector3(&mut prng);
+            assert_eq!(hash.insert_point(i, point), i);
+            points.push(point);
+        }
+
+        for _ in 0..50 {
+            let center = random_vector3(&mut prng);
+            let radius = prng.next_f32() * 15.0;
+            let expected: Vec<usize> = (0..points.len())
+                .filter(|&i| (points[i] - center).magnitude() <= radius)
+                .collect();
+
+            assert_eq!(hash.query_radius(center, radius), expected);
+        }
+    }
+
+    #[test]
+    fn moves_and_removals() {
+        let mut prng = Prng::new(Seed(73));
+        let mut hash = SpatialHash2::new(5.0);
+        let mut points = Vec::new();
+
+        for i in 0..500 {
+            let point = random_vector2(&mut prng);
+            hash.insert_point(i, point);
+            points.push(point);
+        }
+
+        for (key, point) in points.iter_mut().enumerate() {
+            *point += Vector2::new(prng.next_f32() * 4.0 - 2.0, prng.next_f32() * 4.0 - 2.0);
+            assert!(hash.move_point(key, *point));
+        }
+
+        for key in 0..100 {
+            assert_eq!(hash.remove(key), Some(key));
+        }
+        assert_eq!(hash.remove(0), None);
+        assert!(!hash.move_point(0, Vector2::zero()));
+        assert_eq!(hash.len(), 400);
+
+        let region = Rect::new(Vector2::new(-20.0, 30.0), 35.0, -45.0);
+        let expected: Vec<usize> = (100..points.len())
+            .filter(|&i| region.contains(points[i]))
+            .collect();
+
+        assert!(!expected.is_empty());
+        assert_eq!(hash.query_rect(&region), expected);
+    }
+
+    #[test]
+    fn boxes_spanning_several_cells() {
+        let mut prng = Prng::new(Seed(79));
+        let mut hash = SpatialHash3::new(3.0);
+        let mut boxes = Vec::new();
+
+        for i in 0..300 {
+            let aabb = AABB::from_center(
+                random_vector3(&mut prng),
+                Vector3::identity() * (prng.next_f32() * 6.0),
+            );
+            hash.insert(i, &aabb);
+            boxes.push(aabb);
+        }
+
+        let region = Box::new(Vector3::new(-10.0, -20.0, -5.0), 25.0, 30.0, 20.0);
+        let expected: Vec<usize> = (0..boxes.len())
+            .filter(|&i| boxes[i].intersects(&AABB::new(region)))
+            .collect();
+        assert_eq!(hash.query_box(&region), expected);
+
+        let center = Vector3::new(5.0, 0.0, -5.0);
+        let expected: Vec<usize> = (0..boxes.len())
+            .filter(|&i| boxes[i].distance_squared(center) <= 100.0)
+            .collect();
+        assert_eq!(hash.query_radius(center, 10.0), expected);
+    }
+
+    #[test]
+    fn huge_bounds() {
+        let mut hash = SpatialHash2::new(1.0);
+        let near = hash.insert_point("near", Vector2::new(0.5, 0.5));
+        let far = hash.insert_point("far", Vector2::new(1e9, -1e9));
+        let huge = hash.insert("huge", &Rect::square(Vector2::new(-1e30, -1e30), 2e30));
+
+        assert_eq!(hash.query_radius(Vector2::zero(), 1.0), vec![near, huge]);
+        assert_eq!(
+            hash.query_radius(Vector2::zero(), 1e30),
+            vec![near, far, huge]
+        );
+
+        assert!(hash.update(huge, &Rect::square(Vector2::new(5.0, 5.0), 1.0)));
+        assert_eq!(hash.query_radius(Vector2::zero(), 1.0), vec![near]);
+        assert_eq!(
+            hash.query_rect(&Rect::square(Vector2::new(4.0, 4.0), 2.0)),
+            vec![huge]
+        );
+
+        assert!(hash.update(huge, &Rect::square(Vector2::zero(), 1e6)));
+        assert_eq!(hash.remove(huge), Some("huge"));
+        assert_eq!(
+            hash.query_rect(&Rect::square(Vector2::zero(), 1e6)),
+            vec![near]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::container::AABB;
+use crate::coords::BlockPos;
+use crate::vector::Vector3;
+
+/// Box of blocks between two corners, both included
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BlockAABB {
+    pub min: BlockPos,
+    pub max: BlockPos,
+}
+
+impl BlockAABB {
+    /// the corners are sorted so that min is below max on every axis
+    pub fn new(a: BlockPos, b: BlockPos) -> Self {
+        Self {
+            min: BlockPos::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: BlockPos::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    /// blocks overlapping the box
+    pub fn from_aabb(aabb: &AABB) -> Self {
+        let min = BlockPos::from_vector3(aabb.min());
+        let max = aabb.max();
+
+        // a flat box still overlaps the blocks it lies on
+        Self::new(
+            min,
+            BlockPos::new(
+                (max.x.ceil() as i32 - 1).max(min.x),
+                (max.y.ceil() as i32 - 1).max(min.y),
+                (max.z.ceil() as i32 - 1).max(min.z),
+            ),
+        )
+    }
+
+    pub fn to_aabb(&self) -> AABB {
+        AABB::from_min_max(
+            self.min.to_vector3(),
+            self.max.to_vector3() + Vector3::identity(),
+        )
+    }
+
+    /// overlapping part of both boxes
+    pub fn intersection(a: &BlockAABB, b: &BlockAABB) -> Option<Self> {
+        let min = BlockPos::new(
+            a.min.x.max(b.min.x),
+            a.min.y.max(b.min.y),
+            a.min.z.max(b.min.z),
+        );
+        let max = BlockPos::new(
+            a.max.x.min(b.max.x),
+            a.max.y.min(b.max.y),
+            a.max.z.min(b.max.z),
+        );
+
+        if min.x <= max.x && min.y <= max.y && min.z <= max.z {
+            Some(Self { min, max })
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, block: BlockPos) -> bool {
+        (self.min.x..=self.max.x).contains(&block.x)
+            && (self.min.y..=self.max.y).contains(&block.y)
+            && (self.min.z..=self.max.z).contains(&block.z)
+    }
+
+    /// number of blocks in the box
+    pub fn volume(&self) -> u64 {
+        let size = |min: i32, max: i32| (max as i64 - min as i64 + 1) as u64;
+        size(self.min.x, self.max.x) * size(self.min.y, self.max.y) * size(self.min.z, self.max.z)
+    }
+
+    /// every block of the box, ordered by y, then z, then x
+    pub fn iter(&self) -> impl Iterator<Item = BlockPos> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| {
+            (min.z..=max.z).flat_map(move |z| (min.x..=max.x).map(move |x| BlockPos::new(x, y, z)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let aabb = AABB::from_min_max(Vector3::new(-1.5, 0.0, 2.0), Vector3::new(1.0, 0.5, 4.2));
+        let blocks = BlockAABB::from_aabb(&aabb);
+
+        assert_eq!(blocks.min, BlockPos::new(-2, 0, 2));
+        assert_eq!(blocks.max, BlockPos::new(0, 0, 4));
+        assert_eq!(blocks.volume(), 9);
+        assert_eq!(blocks.iter().count(), 9);
+        assert!(blocks.iter().all(|block| blocks.contains(block)));
+        assert_eq!(BlockAABB::from_aabb(&blocks.to_aabb()), blocks);
+    }
+
+    #[test]
+    fn intersection() {
+        let a = BlockAABB::new(BlockPos::new(0, 0, 0), BlockPos::new(4, 4, 4));
+        let b = BlockAABB::new(BlockPos::new(6, 3, 3), BlockPos::new(3, 8, -2));
+
+        let overlap = BlockAABB::intersection(&a, &b).unwrap();
+        assert_eq!(overlap.min, BlockPos::new(3, 3, 0));
+        assert_eq!(overlap.max, BlockPos::new(4, 4, 3));
+
+        let c = BlockAABB::new(BlockPos::new(5, 0, 0), BlockPos::new(6, 0, 0));
+        assert_eq!(BlockAABB::intersection(&a, &c), None);
+    }
+}
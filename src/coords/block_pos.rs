@@ -0,0 +1,171 @@
+use crate::container::AABB;
+use crate::coords::{
+    ChunkDimensions, ChunkPos, ChunkSectionPos, Direction, LocalPos, NEIGHBOUR_OFFSETS,
+};
+use crate::vector::Vector3;
+
+const XZ_BITS: u32 = 26;
+const Y_BITS: u32 = 12;
+
+/// Position of a block in the world
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BlockPos {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl BlockPos {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// block containing the point
+    pub fn from_vector3(point: Vector3) -> Self {
+        Self::new(
+            point.x.floor() as i32,
+            point.y.floor() as i32,
+            point.z.floor() as i32,
+        )
+    }
+
+    pub fn offset(self, x: i32, y: i32, z: i32) -> Self {
+        Self::new(self.x + x, self.y + y, self.z + z)
+    }
+
+    /// minimum corner of the block
+    pub fn to_vector3(self) -> Vector3 {
+        Vector3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+
+    pub fn center(self) -> Vector3 {
+        self.to_vector3() + Vector3::identity() * 0.5
+    }
+
+    pub fn aabb(self) -> AABB {
+        let min = self.to_vector3();
+        AABB::from_min_max(min, min + Vector3::identity())
+    }
+
+    pub fn chunk<D: ChunkDimensions>(self) -> ChunkPos<D> {
+        ChunkPos::new(self.x.div_euclid(D::WIDTH), self.z.div_euclid(D::DEPTH))
+    }
+
+    pub fn section<D: ChunkDimensions>(self) -> ChunkSectionPos<D> {
+        ChunkSectionPos::new(
+            self.x.div_euclid(D::WIDTH),
+            self.y.div_euclid(D::HEIGHT),
+            self.z.div_euclid(D::DEPTH),
+        )
+    }
+
+    /// position within the section containing the block
+    pub fn local<D: ChunkDimensions>(self) -> LocalPos<D> {
+        LocalPos::new(
+            self.x.rem_euclid(D::WIDTH) as u32,
+            self.y.rem_euclid(D::HEIGHT) as u32,
+            self.z.rem_euclid(D::DEPTH) as u32,
+        )
+    }
+
+    /// the 6 blocks sharing a face with this one
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        Direction::all().map(move |direction| self.neighbour(direction))
+    }
+
+    /// the 26 blocks sharing a face, an edge or a corner with this one
+    pub fn all_neighbours(self) -> impl Iterator<Item = Self> {
+        NEIGHBOUR_OFFSETS
+            .iter()
+            .map(move |&[x, y, z]| self.offset(x, y, z))
+    }
+
+    pub fn neighbour(self, direction: Direction) -> Self {
+        let [x, y, z] = direction.offset();
+        self.offset(x, y, z)
+    }
+
+    /// Packs the position in 26 bits for x and z and 12 bits for y, positions outside of
+    /// [-2^25, 2^25) horizontally and [-2048, 2048) vertically wrap around
+    pub fn pack(self) -> u64 {
+        let mask = |bits: u32| (1u64 << bits) - 1;
+
+        ((self.x as u64 & mask(XZ_BITS)) << (XZ_BITS + Y_BITS))
+            | ((self.z as u64 & mask(XZ_BITS)) << Y_BITS)
+            | (self.y as u64 & mask(Y_BITS))
+    }
+
+    pub fn unpack(packed: u64) -> Self {
+        let packed = packed as i64;
+
+        // shifting left then right sign extends each field
+        Self::new(
+            (packed >> (XZ_BITS + Y_BITS)) as i32,
+            (packed << (64 - Y_BITS) >> (64 - Y_BITS)) as i32,
+            (packed << XZ_BITS >> (XZ_BITS + Y_BITS)) as i32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::Chunk16;
+
+    #[test]
+    fn negative_positions() {
+        let block = BlockPos::new(-1, -17, 16);
+
+        assert_eq!(block.chunk::<Chunk16>(), ChunkPos::new(-1, 1));
+        assert_eq!(block.section::<Chunk16>(), ChunkSectionPos::new(-1, -2, 1));
+        assert_eq!(block.local::<Chunk16>(), LocalPos::new(15, 15, 0));
+        assert_eq!(
+            BlockPos::from_vector3(Vector3::new(-0.5, -16.01, 16.99)),
+            block
+        );
+    }
+
+    #[test]
+    fn section_and_local_round_trip() {
+        for x in -40..40 {
+            for &y in [-33, -16, -1, 0, 15, 31].iter() {
+                let block = BlockPos::new(x, y, 7 - x);
+                let section = block.section::<Chunk16>();
+
+                assert_eq!(section.block(block.local()), block);
+                assert!(section.aabb().contains(block.center()));
+            }
+        }
+    }
+
+    #[test]
+    fn pack_round_trip() {
+        let blocks = [
+            BlockPos::new(0, 0, 0),
+            BlockPos::new(-1, -1, -1),
+            BlockPos::new(33_554_431, 2047, -33_554_432),
+            BlockPos::new(-33_554_432, -2048, 33_554_431),
+            BlockPos::new(123_456, -64, -987_654),
+        ];
+
+        for block in blocks.iter() {
+            assert_eq!(BlockPos::unpack(block.pack()), *block);
+        }
+    }
+
+    #[test]
+    fn neighbours_share_a_face() {
+        let block = BlockPos::new(3, -4, 5);
+        let neighbours: Vec<BlockPos> = block.neighbours().collect();
+
+        assert_eq!(neighbours.len(), 6);
+        for (neighbour, direction) in neighbours.into_iter().zip(Direction::all()) {
+            let d = neighbour.to_vector3() - block.to_vector3();
+            assert_eq!(d, direction.normal());
+        }
+        assert_eq!(block.all_neighbours().count(), 26);
+        assert!(block
+            .all_neighbours()
+            .all(|neighbour| (neighbour.center() - block.center()).magnitude() < 1.8));
+    }
+}
@@ -0,0 +1,90 @@
+use std::marker::PhantomData;
+
+use crate::container::AABB;
+use crate::coords::{BlockPos, ChunkDimensions, ChunkSectionPos};
+use crate::vector::Vector3;
+
+const NEIGHBOURS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// Position of a column of chunk sections, in chunks
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ChunkPos<D> {
+    pub x: i32,
+    pub z: i32,
+    dimensions: PhantomData<D>,
+}
+
+impl<D: ChunkDimensions> ChunkPos<D> {
+    pub fn new(x: i32, z: i32) -> Self {
+        Self {
+            x,
+            z,
+            dimensions: PhantomData,
+        }
+    }
+
+    pub fn offset(self, x: i32, z: i32) -> Self {
+        Self::new(self.x + x, self.z + z)
+    }
+
+    /// block with the smallest x and z of the column, at height y
+    pub fn origin(self, y: i32) -> BlockPos {
+        BlockPos::new(self.x * D::WIDTH, y, self.z * D::DEPTH)
+    }
+
+    pub fn section(self, y: i32) -> ChunkSectionPos<D> {
+        ChunkSectionPos::new(self.x, y, self.z)
+    }
+
+    /// bounds of the column between the heights min_y and max_y, in blocks
+    pub fn aabb(self, min_y: f32, max_y: f32) -> AABB {
+        let origin = self.origin(0).to_vector3();
+        AABB::from_min_max(
+            Vector3::new(origin.x, min_y, origin.z),
+            Vector3::new(
+                origin.x + D::WIDTH as f32,
+                max_y,
+                origin.z + D::DEPTH as f32,
+            ),
+        )
+    }
+
+    /// the 4 columns sharing a side with this one
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        NEIGHBOURS.iter().map(move |&(x, z)| self.offset(x, z))
+    }
+
+    /// Packs x in the low and z in the high 32 bits
+    pub fn pack(self) -> u64 {
+        (self.x as u32 as u64) | ((self.z as u32 as u64) << 32)
+    }
+
+    pub fn unpack(packed: u64) -> Self {
+        Self::new(packed as u32 as i32, (packed >> 32) as u32 as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::Chunk16;
+
+    #[test]
+    fn column_bounds() {
+        let chunk = ChunkPos::<Chunk16>::new(-2, 3);
+        let aabb = chunk.aabb(-64.0, 320.0);
+
+        assert_eq!(aabb.min(), Vector3::new(-32.0, -64.0, 48.0));
+        assert_eq!(aabb.max(), Vector3::new(-16.0, 320.0, 64.0));
+        assert_eq!(chunk.origin(5).chunk(), chunk);
+        assert_eq!(chunk.section(-4).chunk(), chunk);
+    }
+
+    #[test]
+    fn pack_round_trip() {
+        for &(x, z) in [(0, 0), (-1, 1), (i32::MIN, i32::MAX), (1234, -5678)].iter() {
+            let chunk = ChunkPos::<Chunk16>::new(x, z);
+            assert_eq!(ChunkPos::unpack(chunk.pack()), chunk);
+        }
+    }
+}
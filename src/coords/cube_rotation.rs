@@ -0,0 +1,259 @@
+use std::ops;
+
+use crate::coords::{Axis, Direction};
+use crate::matrix::Matrix4;
+use crate::quaternion::Quaternion;
+
+/// Orderings of the axes, the odd ones swap two axes
+const PERMUTATIONS: [[u8; 3]; 6] = [
+    [0, 1, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [0, 2, 1],
+    [2, 1, 0],
+    [1, 0, 2],
+];
+
+/// Rotation, possibly mirrored, mapping the cube onto itself
+///
+/// Every component of the result is a component of the input, possibly negated, so integer
+/// positions and directions are transformed exactly. Encoded as a u8 below 48, the 24 proper
+/// rotations come first and 0 is the identity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CubeRotation {
+    /// for each component of the result, the component of the input it comes from
+    axes: [u8; 3],
+    signs: [i8; 3],
+}
+
+impl CubeRotation {
+    pub fn identity() -> Self {
+        Self {
+            axes: [0, 1, 2],
+            signs: [1, 1, 1],
+        }
+    }
+
+    /// the 24 rotations without mirroring
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..24).map(|code| Self::from_u8(code).unwrap())
+    }
+
+    /// the 48 rotations, the last 24 being mirrored
+    pub fn all_with_mirrors() -> impl Iterator<Item = Self> {
+        (0..48).map(|code| Self::from_u8(code).unwrap())
+    }
+
+    /// counterclockwise quarter turns around the axis, looking from its positive side
+    pub fn around(axis: Axis, quarter_turns: i32) -> Self {
+        let turn = match axis {
+            Axis::X => Self {
+                axes: [0, 2, 1],
+                signs: [1, -1, 1],
+            },
+            Axis::Y => Self {
+                axes: [2, 1, 0],
+                signs: [1, 1, -1],
+            },
+            Axis::Z => Self {
+                axes: [1, 0, 2],
+                signs: [-1, 1, 1],
+            },
+        };
+
+        (0..quarter_turns.rem_euclid(4)).fold(Self::identity(), |rotation, _| turn * rotation)
+    }
+
+    /// reflection across the plane perpendicular to the axis
+    pub fn mirror(axis: Axis) -> Self {
+        let mut signs = [1; 3];
+        signs[axis as usize] = -1;
+
+        Self {
+            axes: [0, 1, 2],
+            signs,
+        }
+    }
+
+    pub fn from_u8(code: u8) -> Option<Self> {
+        if code >= 48 {
+            return None;
+        }
+
+        // proper rotations have an even permutation with an even number of negations, or an odd
+        // permutation with an odd number of them
+        let mirrored = code >= 24;
+        let code = code % 24;
+        let permutation = (code / 4) as usize;
+        let odd = (permutation >= 3) != mirrored;
+
+        let mut negations = code % 4;
+        let parity = (negations.count_ones() % 2 == 1) != odd;
+        negations |= (parity as u8) << 2;
+
+        let mut signs = [1; 3];
+        for (i, sign) in signs.iter_mut().enumerate() {
+            if negations & (1 << i) != 0 {
+                *sign = -1;
+            }
+        }
+
+        Some(Self {
+            axes: PERMUTATIONS[permutation],
+            signs,
+        })
+    }
+
+    pub fn to_u8(self) -> u8 {
+        let permutation = PERMUTATIONS
+            .iter()
+            .position(|axes| *axes == self.axes)
+            .unwrap() as u8;
+        let negations = (self.signs[0] < 0) as u8 | ((self.signs[1] < 0) as u8) << 1;
+
+        permutation * 4 + negations + if self.is_mirrored() { 24 } else { 0 }
+    }
+
+    pub fn is_mirrored(self) -> bool {
+        let odd = PERMUTATIONS[3..].contains(&self.axes);
+        let negative = self.signs.iter().product::<i8>() < 0;
+        odd != negative
+    }
+
+    pub fn inverse(self) -> Self {
+        let mut inverse = Self::identity();
+        for i in 0..3 {
+            let axis = self.axes[i] as usize;
+            inverse.axes[axis] = i as u8;
+            inverse.signs[axis] = self.signs[i];
+        }
+        inverse
+    }
+
+    pub fn apply(self, v: [i32; 3]) -> [i32; 3] {
+        let component = |i: usize| self.signs[i] as i32 * v[self.axes[i] as usize];
+        [component(0), component(1), component(2)]
+    }
+
+    pub fn rotate(self, direction: Direction) -> Direction {
+        let [x, y, z] = self.apply(direction.offset());
+        Direction::all()
+            .find(|direction| direction.offset() == [x, y, z])
+            .unwrap()
+    }
+
+    pub fn to_matrix(self) -> Matrix4 {
+        let mut m = Matrix4::identity();
+        for i in 0..3 {
+            m[i] = [0.0; 4];
+            m[i][self.axes[i] as usize] = self.signs[i] as f32;
+        }
+        m
+    }
+
+    /// None for mirrored rotations, which quaternions cannot represent
+    pub fn to_quaternion(self) -> Option<Quaternion> {
+        if self.is_mirrored() {
+            None
+        } else {
+            Some(Quaternion::from_rotation_matrix(&self.to_matrix()))
+        }
+    }
+}
+
+impl Default for CubeRotation {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// a * b applies b first
+impl ops::Mul for CubeRotation {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut result = Self::identity();
+        for i in 0..3 {
+            let axis = self.axes[i] as usize;
+            result.axes[i] = rhs.axes[axis];
+            result.signs[i] = self.signs[i] * rhs.signs[axis];
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn encoding_round_trip() {
+        let rotations: Vec<CubeRotation> = CubeRotation::all_with_mirrors().collect();
+        let unique: HashSet<CubeRotation> = rotations.iter().copied().collect();
+
+        assert_eq!(unique.len(), 48);
+        assert_eq!(CubeRotation::from_u8(0), Some(CubeRotation::identity()));
+        assert_eq!(CubeRotation::from_u8(48), None);
+
+        for (code, rotation) in rotations.iter().enumerate() {
+            assert_eq!(rotation.to_u8() as usize, code);
+            assert_eq!(rotation.is_mirrored(), code >= 24);
+        }
+    }
+
+    #[test]
+    fn group_properties() {
+        let proper: HashSet<CubeRotation> = CubeRotation::all().collect();
+
+        for a in CubeRotation::all_with_mirrors() {
+            assert_eq!(a * a.inverse(), CubeRotation::identity());
+            assert_eq!(a.inverse() * a, CubeRotation::identity());
+
+            for b in CubeRotation::all_with_mirrors() {
+                let ab = a * b;
+                assert_eq!(ab.is_mirrored(), a.is_mirrored() != b.is_mirrored());
+                assert_eq!(ab.apply([1, 2, 3]), a.apply(b.apply([1, 2, 3])));
+
+                if !ab.is_mirrored() {
+                    assert!(proper.contains(&ab));
+                }
+            }
+        }
+
+        let mirror = CubeRotation::mirror(Axis::Y);
+        assert!(mirror.is_mirrored());
+        assert_eq!(mirror.rotate(Direction::Up), Direction::Down);
+        assert_eq!(mirror.to_quaternion(), None);
+    }
+
+    #[test]
+    fn matches_quaternions() {
+        for rotation in CubeRotation::all() {
+            let quaternion = rotation.to_quaternion().unwrap();
+
+            for direction in Direction::all() {
+                let rotated = quaternion.rotate(direction.normal());
+                assert!((rotated - rotation.rotate(direction).normal()).magnitude() < 1e-5);
+            }
+        }
+
+        let axes = [Axis::X, Axis::Y, Axis::Z];
+        for &axis in axes.iter() {
+            for turns in -2..6 {
+                let rotation = CubeRotation::around(axis, turns);
+                for direction in Direction::all() {
+                    assert_eq!(
+                        rotation.rotate(direction),
+                        direction.rotate_around(axis, turns)
+                    );
+                }
+            }
+        }
+
+        let quaternion = CubeRotation::around(Axis::Y, 1).to_quaternion().unwrap();
+        let expected = Quaternion::from_axis_angle(crate::vector::Vector3::up(), FRAC_PI_2);
+        assert!((Quaternion::dot(quaternion, expected).abs() - 1.0).abs() < 1e-5);
+    }
+}
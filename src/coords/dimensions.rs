@@ -0,0 +1,27 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// Size in blocks of the chunk sections of a world
+///
+/// Chunks are columns of sections stacked along y, so only the section height is fixed.
+pub trait ChunkDimensions: Copy + Clone + Debug + Default + PartialEq + Eq + Hash {
+    /// size along x
+    const WIDTH: i32;
+    /// size of a section along y
+    const HEIGHT: i32;
+    /// size along z
+    const DEPTH: i32;
+
+    /// number of blocks in a section
+    const VOLUME: usize = (Self::WIDTH * Self::HEIGHT * Self::DEPTH) as usize;
+}
+
+/// Sections of 16 blocks in every direction
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Chunk16;
+
+impl ChunkDimensions for Chunk16 {
+    const WIDTH: i32 = 16;
+    const HEIGHT: i32 = 16;
+    const DEPTH: i32 = 16;
+}
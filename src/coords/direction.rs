@@ -0,0 +1,229 @@
+use crate::quaternion::Quaternion;
+use crate::vector::Vector3;
+
+/// Offsets of the 26 blocks surrounding a block, faces, then edges, then corners
+pub const NEIGHBOUR_OFFSETS: [[i32; 3]; 26] = [
+    [1, 0, 0],
+    [-1, 0, 0],
+    [0, 1, 0],
+    [0, -1, 0],
+    [0, 0, 1],
+    [0, 0, -1],
+    [1, 1, 0],
+    [1, -1, 0],
+    [-1, 1, 0],
+    [-1, -1, 0],
+    [1, 0, 1],
+    [1, 0, -1],
+    [-1, 0, 1],
+    [-1, 0, -1],
+    [0, 1, 1],
+    [0, 1, -1],
+    [0, -1, 1],
+    [0, -1, -1],
+    [1, 1, 1],
+    [1, 1, -1],
+    [1, -1, 1],
+    [1, -1, -1],
+    [-1, 1, 1],
+    [-1, 1, -1],
+    [-1, -1, 1],
+    [-1, -1, -1],
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// One of the six faces of a cube, named like the matching Vector3 constructors
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Direction {
+    Right,
+    Left,
+    Up,
+    Down,
+    Forward,
+    Backward,
+}
+
+impl Direction {
+    /// every direction, in index order
+    pub const ALL: [Direction; 6] = [
+        Direction::Right,
+        Direction::Left,
+        Direction::Up,
+        Direction::Down,
+        Direction::Forward,
+        Direction::Backward,
+    ];
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        Self::ALL.iter().copied()
+    }
+
+    /// position of the direction in ALL, to index per face arrays
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    /// direction of the largest component of the normal, None for a zero or non finite normal
+    pub fn from_normal(normal: Vector3) -> Option<Self> {
+        let abs = normal.abs();
+        if !(abs.x.is_finite() && abs.y.is_finite() && abs.z.is_finite()) {
+            return None;
+        }
+
+        let (value, axis) = if abs.x >= abs.y && abs.x >= abs.z {
+            (normal.x, Axis::X)
+        } else if abs.y >= abs.z {
+            (normal.y, Axis::Y)
+        } else {
+            (normal.z, Axis::Z)
+        };
+
+        if value == 0.0 {
+            None
+        } else {
+            Some(Self::from_axis(axis, value > 0.0))
+        }
+    }
+
+    pub fn from_axis(axis: Axis, positive: bool) -> Self {
+        match (axis, positive) {
+            (Axis::X, true) => Direction::Right,
+            (Axis::X, false) => Direction::Left,
+            (Axis::Y, true) => Direction::Up,
+            (Axis::Y, false) => Direction::Down,
+            (Axis::Z, true) => Direction::Forward,
+            (Axis::Z, false) => Direction::Backward,
+        }
+    }
+
+    pub fn axis(self) -> Axis {
+        match self {
+            Direction::Right | Direction::Left => Axis::X,
+            Direction::Up | Direction::Down => Axis::Y,
+            Direction::Forward | Direction::Backward => Axis::Z,
+        }
+    }
+
+    pub fn is_positive(self) -> bool {
+        matches!(self, Direction::Right | Direction::Up | Direction::Forward)
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::from_axis(self.axis(), !self.is_positive())
+    }
+
+    /// offset to the neighbouring block on this side
+    pub fn offset(self) -> [i32; 3] {
+        NEIGHBOUR_OFFSETS[self.index()]
+    }
+
+    pub fn normal(self) -> Vector3 {
+        let [x, y, z] = self.offset();
+        Vector3::new(x as f32, y as f32, z as f32)
+    }
+
+    /// direction closest to the rotated normal
+    pub fn rotate(self, rotation: &Quaternion) -> Self {
+        Self::from_normal(rotation.rotate(self.normal())).unwrap_or(self)
+    }
+
+    /// Rotates counterclockwise by quarter turns around the axis, looking from its positive
+    /// side, as Quaternion::from_axis_angle does
+    pub fn rotate_around(self, axis: Axis, quarter_turns: i32) -> Self {
+        let mut direction = self;
+        for _ in 0..quarter_turns.rem_euclid(4) {
+            let [x, y, z] = direction.offset();
+            let [x, y, z] = match axis {
+                Axis::X => [x, -z, y],
+                Axis::Y => [z, y, -x],
+                Axis::Z => [-y, x, z],
+            };
+            direction = Self::from_offset([x, y, z]).unwrap();
+        }
+        direction
+    }
+
+    fn from_offset(offset: [i32; 3]) -> Option<Self> {
+        Self::all().find(|direction| direction.offset() == offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn opposites_and_axes() {
+        for direction in Direction::all() {
+            let opposite = direction.opposite();
+
+            assert_ne!(opposite, direction);
+            assert_eq!(opposite.opposite(), direction);
+            assert_eq!(opposite.axis(), direction.axis());
+            assert_eq!(opposite.normal(), -direction.normal());
+            assert_eq!(Direction::from_index(direction.index()), Some(direction));
+            assert_eq!(Direction::from_normal(direction.normal()), Some(direction));
+        }
+
+        assert_eq!(Direction::Up.normal(), Vector3::up());
+        assert_eq!(Direction::Backward.normal(), Vector3::backward());
+        assert_eq!(Direction::from_index(6), None);
+    }
+
+    #[test]
+    fn hit_normals() {
+        let normal = Vector3::new(0.1, -0.9, 0.3);
+        assert_eq!(Direction::from_normal(normal), Some(Direction::Down));
+        assert_eq!(Direction::from_normal(Vector3::zero()), None);
+        assert_eq!(
+            Direction::from_normal(Vector3::new(f32::NAN, 1.0, 0.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn quarter_turns_match_quaternions() {
+        let axes = [
+            (Axis::X, Vector3::right()),
+            (Axis::Y, Vector3::up()),
+            (Axis::Z, Vector3::forward()),
+        ];
+
+        for &(axis, vector) in axes.iter() {
+            for turns in -4..8 {
+                let rotation = Quaternion::from_axis_angle(vector, FRAC_PI_2 * turns as f32);
+                for direction in Direction::all() {
+                    assert_eq!(
+                        direction.rotate_around(axis, turns),
+                        direction.rotate(&rotation)
+                    );
+                }
+            }
+        }
+
+        assert_eq!(
+            Direction::Right.rotate_around(Axis::Y, 1),
+            Direction::Backward
+        );
+    }
+
+    #[test]
+    fn neighbour_offsets_are_unique() {
+        for (i, a) in NEIGHBOUR_OFFSETS.iter().enumerate() {
+            assert!(a.iter().any(|c| *c != 0));
+            assert!(NEIGHBOUR_OFFSETS[i + 1..].iter().all(|b| a != b));
+        }
+    }
+}
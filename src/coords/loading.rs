@@ -0,0 +1,287 @@
+use crate::container::{Frustum, AABB};
+use crate::coords::{ChunkDimensions, ChunkPos, ChunkSectionPos};
+
+/// Columns around a center, ring after ring of increasing chebyshev distance
+///
+/// Each ring starts on the positive x side and turns towards positive z.
+pub struct Spiral<D> {
+    center: ChunkPos<D>,
+    radius: i32,
+    ring: i32,
+    index: i32,
+}
+
+impl<D: ChunkDimensions> Spiral<D> {
+    /// radius: chebyshev distance of the last ring, the spiral covers a square of 2 * radius + 1
+    /// columns
+    pub fn new(center: ChunkPos<D>, radius: i32) -> Self {
+        Self {
+            center,
+            radius,
+            ring: 0,
+            index: 0,
+        }
+    }
+}
+
+impl<D: ChunkDimensions> Iterator for Spiral<D> {
+    type Item = ChunkPos<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let r = self.ring;
+        if r > self.radius {
+            return None;
+        }
+
+        // ring r > 0 is made of 4 sides of 2 * r columns
+        let (x, z) = if r == 0 {
+            (0, 0)
+        } else {
+            let t = self.index % (2 * r);
+            match self.index / (2 * r) {
+                0 => (r, -r + 1 + t),
+                1 => (r - 1 - t, r),
+                2 => (-r, r - 1 - t),
+                _ => (-r + 1 + t, -r),
+            }
+        };
+
+        self.index += 1;
+        if self.index >= (8 * r).max(1) {
+            self.ring += 1;
+            self.index = 0;
+        }
+
+        Some(self.center.offset(x, z))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // a negative radius yields nothing, even though its side squared is positive
+        if self.radius < 0 {
+            return (0, Some(0));
+        }
+
+        let side = 2 * self.radius as i64 + 1;
+        let before = match self.ring {
+            0 => 0,
+            ring => (2 * ring as i64 - 1).pow(2) + self.index as i64,
+        };
+        let remaining = (side * side - before).max(0) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<D: ChunkDimensions> ExactSizeIterator for Spiral<D> {}
+
+/// Positions that entered and left the range when its center moved
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeDelta<P> {
+    /// sorted by distance to the new center
+    pub entered: Vec<P>,
+    pub left: Vec<P>,
+}
+
+/// Columns at most radius chunks away from the center, nearest first
+pub fn circle<D: ChunkDimensions>(
+    center: ChunkPos<D>,
+    radius: i32,
+) -> impl Iterator<Item = ChunkPos<D>> {
+    let mut chunks: Vec<ChunkPos<D>> = Spiral::new(center, radius)
+        .filter(|chunk| column_distance(*chunk, center) <= radius_squared(radius))
+        .collect();
+
+    // stable, so equally distant columns keep their spiral order
+    chunks.sort_by_key(|chunk| column_distance(*chunk, center));
+    chunks.into_iter()
+}
+
+/// Sections at most radius sections away from the center, nearest first
+pub fn sphere<D: ChunkDimensions>(
+    center: ChunkSectionPos<D>,
+    radius: i32,
+) -> impl Iterator<Item = ChunkSectionPos<D>> {
+    let mut sections = Vec::new();
+    for y in -radius..=radius {
+        for column in Spiral::new(center.chunk(), radius) {
+            let section = column.section(center.y + y);
+            if section_distance(section, center) <= radius_squared(radius) {
+                sections.push(section);
+            }
+        }
+    }
+
+    sections.sort_by_key(|section| section_distance(*section, center));
+    sections.into_iter()
+}
+
+/// Columns to load and unload when the center of a circle moves
+pub fn circle_delta<D: ChunkDimensions>(
+    from: ChunkPos<D>,
+    to: ChunkPos<D>,
+    radius: i32,
+) -> RangeDelta<ChunkPos<D>> {
+    let outside =
+        |chunk: &ChunkPos<D>, center| column_distance(*chunk, center) > radius_squared(radius);
+
+    RangeDelta {
+        entered: circle(to, radius)
+            .filter(|chunk| outside(chunk, from))
+            .collect(),
+        left: circle(from, radius)
+            .filter(|chunk| outside(chunk, to))
+            .collect(),
+    }
+}
+
+/// Sections to load and unload when the center of a sphere moves
+pub fn sphere_delta<D: ChunkDimensions>(
+    from: ChunkSectionPos<D>,
+    to: ChunkSectionPos<D>,
+    radius: i32,
+) -> RangeDelta<ChunkSectionPos<D>> {
+    let outside = |section: &ChunkSectionPos<D>, center| {
+        section_distance(*section, center) > radius_squared(radius)
+    };
+
+    RangeDelta {
+        entered: sphere(to, radius)
+            .filter(|section| outside(section, from))
+            .collect(),
+        left: sphere(from, radius)
+            .filter(|section| outside(section, to))
+            .collect(),
+    }
+}
+
+/// Moves the positions whose bounds are in the frustum to the front, keeping the order of the
+/// visible and of the hidden positions otherwise
+pub fn visible_first<P, I, F>(positions: I, frustum: &Frustum, aabb: F) -> Vec<P>
+where
+    I: IntoIterator<Item = P>,
+    F: Fn(&P) -> AABB,
+{
+    let (mut visible, hidden): (Vec<P>, Vec<P>) = positions
+        .into_iter()
+        .partition(|position| frustum.contains(&aabb(position)));
+
+    visible.extend(hidden);
+    visible
+}
+
+fn radius_squared(radius: i32) -> i64 {
+    radius as i64 * radius as i64
+}
+
+fn column_distance<D: ChunkDimensions>(a: ChunkPos<D>, b: ChunkPos<D>) -> i64 {
+    let (x, z) = ((a.x - b.x) as i64, (a.z - b.z) as i64);
+    x * x + z * z
+}
+
+fn section_distance<D: ChunkDimensions>(a: ChunkSectionPos<D>, b: ChunkSectionPos<D>) -> i64 {
+    let (x, y, z) = ((a.x - b.x) as i64, (a.y - b.y) as i64, (a.z - b.z) as i64);
+    x * x + y * y + z * z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::Chunk16;
+    use crate::matrix::Matrix4;
+    use std::collections::HashSet;
+
+    type Chunk = ChunkPos<Chunk16>;
+    type Section = ChunkSectionPos<Chunk16>;
+
+    #[test]
+    fn spiral_covers_the_square_in_rings() {
+        let center = Chunk::new(-3, 5);
+        let spiral = Spiral::new(center, 4);
+        assert_eq!(spiral.len(), 81);
+
+        let chunks: Vec<Chunk> = spiral.collect();
+        let unique: HashSet<Chunk> = chunks.iter().copied().collect();
+        assert_eq!(unique.len(), 81);
+        assert_eq!(chunks[0], center);
+
+        let ring = |chunk: &Chunk| (chunk.x - center.x).abs().max((chunk.z - center.z).abs());
+        assert!(chunks.windows(2).all(|w| ring(&w[0]) <= ring(&w[1])));
+        assert!(chunks.iter().all(|chunk| ring(chunk) <= 4));
+
+        // consecutive columns of a ring are adjacent
+        for w in chunks[1..9].windows(2) {
+            assert_eq!((w[0].x - w[1].x).abs() + (w[0].z - w[1].z).abs(), 1);
+        }
+    }
+
+    #[test]
+    fn negative_radius_is_empty() {
+        let spiral = Spiral::new(Chunk::new(0, 0), -2);
+        assert_eq!(spiral.len(), 0);
+        assert_eq!(spiral.count(), 0);
+    }
+
+    #[test]
+    fn circle_and_sphere_are_sorted() {
+        let center = Chunk::new(10, -10);
+        let chunks: Vec<Chunk> = circle(center, 6).collect();
+        let expected = (-6..=6)
+            .flat_map(|x| (-6..=6).map(move |z| (x, z)))
+            .filter(|(x, z)| x * x + z * z <= 36)
+            .count();
+
+        assert_eq!(chunks.len(), expected);
+        assert_eq!(chunks[0], center);
+        assert!(chunks
+            .windows(2)
+            .all(|w| column_distance(w[0], center) <= column_distance(w[1], center)));
+
+        let center = Section::new(0, -2, 4);
+        let sections: Vec<Section> = sphere(center, 3).collect();
+        assert_eq!(sections.len(), 123);
+        assert!(sections
+            .windows(2)
+            .all(|w| section_distance(w[0], center) <= section_distance(w[1], center)));
+    }
+
+    #[test]
+    fn delta_matches_set_difference() {
+        let (from, to) = (Chunk::new(0, 0), Chunk::new(2, -1));
+        let before: HashSet<Chunk> = circle(from, 5).collect();
+        let after: HashSet<Chunk> = circle(to, 5).collect();
+        let delta = circle_delta(from, to, 5);
+
+        let entered: HashSet<Chunk> = delta.entered.iter().copied().collect();
+        let left: HashSet<Chunk> = delta.left.iter().copied().collect();
+        assert_eq!(entered, after.difference(&before).copied().collect());
+        assert_eq!(left, before.difference(&after).copied().collect());
+
+        let delta = sphere_delta(Section::new(0, 0, 0), Section::new(0, 0, 0), 4);
+        assert!(delta.entered.is_empty() && delta.left.is_empty());
+
+        let delta = sphere_delta(Section::new(0, 0, 0), Section::new(0, 1, 0), 4);
+        assert!(delta.entered.iter().all(|section| section.y > 0));
+        assert!(delta.left.iter().all(|section| section.y < 1));
+    }
+
+    #[test]
+    fn visible_sections_come_first() {
+        // sees x from 1 to 65 blocks, and far along y and z
+        let mut m = Matrix4::identity();
+        m[0][0] = 1.0 / 32.0;
+        m[0][3] = -33.0 / 32.0;
+        m[1][1] = 1.0 / 1000.0;
+        m[2][2] = 1.0 / 1000.0;
+        let frustum = Frustum::new(&m);
+
+        let sections: Vec<Section> = sphere(Section::new(0, 0, 0), 3).collect();
+        let ordered = visible_first(sections.iter().copied(), &frustum, |s| s.aabb());
+
+        let split = ordered.iter().position(|section| section.x < 0).unwrap();
+        assert!(ordered[..split].iter().all(|section| section.x >= 0));
+        assert!(ordered[split..].iter().all(|section| section.x < 0));
+        assert!(ordered[..split]
+            .windows(2)
+            .all(|w| section_distance(w[0], Section::new(0, 0, 0))
+                <= section_distance(w[1], Section::new(0, 0, 0))));
+    }
+}
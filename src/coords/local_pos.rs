@@ -0,0 +1,83 @@
+use std::marker::PhantomData;
+
+use crate::coords::ChunkDimensions;
+
+/// Position of a block within its chunk section
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LocalPos<D> {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+    dimensions: PhantomData<D>,
+}
+
+impl<D: ChunkDimensions> LocalPos<D> {
+    pub fn new(x: u32, y: u32, z: u32) -> Self {
+        assert!(
+            x < D::WIDTH as u32 && y < D::HEIGHT as u32 && z < D::DEPTH as u32,
+            "local position ({}, {}, {}) outside of the section",
+            x,
+            y,
+            z
+        );
+
+        Self {
+            x,
+            y,
+            z,
+            dimensions: PhantomData,
+        }
+    }
+
+    /// Index of the block in an array holding a section, ordered by y, then z, then x
+    pub fn index(self) -> usize {
+        let (width, depth) = (D::WIDTH as usize, D::DEPTH as usize);
+        (self.y as usize * depth + self.z as usize) * width + self.x as usize
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        let (width, depth) = (D::WIDTH as usize, D::DEPTH as usize);
+        Self::new(
+            (index % width) as u32,
+            (index / (width * depth)) as u32,
+            (index / width % depth) as u32,
+        )
+    }
+
+    /// every position of a section, in index order
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..D::VOLUME).map(Self::from_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::Chunk16;
+
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+    struct Flat;
+
+    impl ChunkDimensions for Flat {
+        const WIDTH: i32 = 8;
+        const HEIGHT: i32 = 2;
+        const DEPTH: i32 = 4;
+    }
+
+    #[test]
+    fn index_round_trip() {
+        assert_eq!(LocalPos::<Chunk16>::all().count(), 4096);
+        assert_eq!(LocalPos::<Flat>::all().count(), 64);
+
+        for (i, local) in LocalPos::<Flat>::all().enumerate() {
+            assert_eq!(local.index(), i);
+        }
+        assert_eq!(LocalPos::<Flat>::new(7, 1, 3).index(), 63);
+    }
+
+    #[test]
+    #[should_panic]
+    fn outside_of_the_section() {
+        LocalPos::<Flat>::new(0, 2, 0);
+    }
+}
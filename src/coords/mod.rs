@@ -0,0 +1,23 @@
+mod block_aabb;
+mod block_pos;
+mod chunk_pos;
+mod cube_rotation;
+mod dimensions;
+mod direction;
+mod loading;
+mod local_pos;
+mod raster;
+mod section_pos;
+
+pub use self::block_aabb::BlockAABB;
+pub use self::block_pos::BlockPos;
+pub use self::chunk_pos::ChunkPos;
+pub use self::cube_rotation::CubeRotation;
+pub use self::dimensions::{Chunk16, ChunkDimensions};
+pub use self::direction::{Axis, Direction, NEIGHBOUR_OFFSETS};
+pub use self::loading::{
+    circle, circle_delta, sphere, sphere_delta, visible_first, RangeDelta, Spiral,
+};
+pub use self::local_pos::LocalPos;
+pub use self::raster::{rasterize, rasterize_within, Fill, Volume};
+pub use self::section_pos::ChunkSectionPos;
@@ -0,0 +1,235 @@
+use crate::container::{AABB, OBB};
+use crate::coords::{BlockAABB, BlockPos};
+use crate::geometry::{Capsule, Cone, Cylinder, Ellipsoid, Sphere};
+use crate::vector::Vector3;
+
+/// Shape whose blocks can be enumerated, a block is inside when its center is
+pub trait Volume {
+    fn contains(&self, point: Vector3) -> bool;
+
+    fn aabb(&self) -> AABB;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Fill {
+    /// every block inside the shape
+    Solid,
+    /// blocks inside the shape sharing a face with a block outside of it
+    Shell,
+}
+
+/// Blocks of the shape, ordered by y, then z, then x
+pub fn rasterize<V: Volume>(volume: &V, fill: Fill) -> impl Iterator<Item = BlockPos> + '_ {
+    cells(volume, fill, candidates(volume))
+}
+
+/// Blocks of the shape within the bounds, in the same order as rasterize
+///
+/// Only blocks outside of the shape make the shell, so cutting a shape with the bounds does not
+/// add faces where it was cut.
+pub fn rasterize_within<'a, V: Volume>(
+    volume: &'a V,
+    fill: Fill,
+    bounds: &BlockAABB,
+) -> impl Iterator<Item = BlockPos> + 'a {
+    let blocks = candidates(volume).and_then(|blocks| BlockAABB::intersection(&blocks, bounds));
+    cells(volume, fill, blocks)
+}
+
+/// blocks whose center may be inside the shape, None when it is too thin to hold any
+fn candidates<V: Volume>(volume: &V) -> Option<BlockAABB> {
+    let bounds = volume.aabb();
+    let half = Vector3::identity() * 0.5;
+    let (min, max) = (bounds.min() - half, bounds.max() - half);
+
+    let min = BlockPos::new(
+        min.x.ceil() as i32,
+        min.y.ceil() as i32,
+        min.z.ceil() as i32,
+    );
+    let max = BlockPos::from_vector3(max);
+
+    if min.x <= max.x && min.y <= max.y && min.z <= max.z {
+        Some(BlockAABB { min, max })
+    } else {
+        None
+    }
+}
+
+fn cells<V: Volume>(
+    volume: &V,
+    fill: Fill,
+    blocks: Option<BlockAABB>,
+) -> impl Iterator<Item = BlockPos> + '_ {
+    blocks
+        .into_iter()
+        .flat_map(|blocks| blocks.iter())
+        .filter(move |block| volume.contains(block.center()))
+        .filter(move |block| match fill {
+            Fill::Solid => true,
+            Fill::Shell => block
+                .neighbours()
+                .any(|neighbour| !volume.contains(neighbour.center())),
+        })
+}
+
+impl Volume for Sphere {
+    fn contains(&self, point: Vector3) -> bool {
+        Sphere::contains(self, point)
+    }
+
+    fn aabb(&self) -> AABB {
+        Sphere::aabb(self)
+    }
+}
+
+impl Volume for Ellipsoid {
+    fn contains(&self, point: Vector3) -> bool {
+        Ellipsoid::contains(self, point)
+    }
+
+    fn aabb(&self) -> AABB {
+        Ellipsoid::aabb(self)
+    }
+}
+
+impl Volume for Cylinder {
+    fn contains(&self, point: Vector3) -> bool {
+        Cylinder::contains(self, point)
+    }
+
+    fn aabb(&self) -> AABB {
+        Cylinder::aabb(self)
+    }
+}
+
+impl Volume for Cone {
+    fn contains(&self, point: Vector3) -> bool {
+        Cone::contains(self, point)
+    }
+
+    fn aabb(&self) -> AABB {
+        Cone::aabb(self)
+    }
+}
+
+impl Volume for Capsule {
+    fn contains(&self, point: Vector3) -> bool {
+        Capsule::contains(self, point)
+    }
+
+    fn aabb(&self) -> AABB {
+        Capsule::aabb(self)
+    }
+}
+
+impl Volume for OBB {
+    fn contains(&self, point: Vector3) -> bool {
+        OBB::contains(self, point)
+    }
+
+    fn aabb(&self) -> AABB {
+        OBB::aabb(self)
+    }
+}
+
+impl Volume for AABB {
+    fn contains(&self, point: Vector3) -> bool {
+        AABB::contains(self, point)
+    }
+
+    fn aabb(&self) -> AABB {
+        *self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// blocks inside the shape found by testing a generous box around it
+    fn brute_force<V: Volume>(volume: &V) -> Vec<BlockPos> {
+        let blocks = BlockAABB::from_aabb(&volume.aabb().expanded(2.0));
+        blocks
+            .iter()
+            .filter(|block| volume.contains(block.center()))
+            .collect()
+    }
+
+    #[test]
+    fn solid_shapes_match_brute_force() {
+        let center = Vector3::new(0.5, 10.5, -3.5);
+        let sphere = Sphere::new(center, 4.3);
+        let ellipsoid = Ellipsoid::new(center, Vector3::new(6.0, 2.5, 3.2));
+        let cylinder = Cylinder::new(center, center + Vector3::new(3.0, 7.0, -2.0), 2.2);
+        let cone = Cone::new(center, center + Vector3::new(0.0, 9.0, 0.0), 4.0);
+        let disc = Cylinder::disc(center, Vector3::up(), 5.0);
+
+        assert_eq!(
+            rasterize(&sphere, Fill::Solid).collect::<Vec<_>>(),
+            brute_force(&sphere)
+        );
+        assert_eq!(
+            rasterize(&ellipsoid, Fill::Solid).collect::<Vec<_>>(),
+            brute_force(&ellipsoid)
+        );
+        assert_eq!(
+            rasterize(&cylinder, Fill::Solid).collect::<Vec<_>>(),
+            brute_force(&cylinder)
+        );
+        assert_eq!(
+            rasterize(&cone, Fill::Solid).collect::<Vec<_>>(),
+            brute_force(&cone)
+        );
+
+        let blocks: Vec<BlockPos> = rasterize(&disc, Fill::Solid).collect();
+        assert_eq!(blocks, brute_force(&disc));
+        assert!(blocks.iter().all(|block| block.y == 10));
+        assert_eq!(blocks.len(), 81);
+    }
+
+    #[test]
+    fn shell_surrounds_the_inside() {
+        let sphere = Sphere::new(Vector3::new(0.5, 0.5, 0.5), 5.0);
+        let solid: HashSet<BlockPos> = rasterize(&sphere, Fill::Solid).collect();
+        let shell: HashSet<BlockPos> = rasterize(&sphere, Fill::Shell).collect();
+
+        assert!(shell.is_subset(&solid));
+        assert!(shell.len() < solid.len());
+
+        // the blocks inside the shell never touch the outside
+        for block in solid.difference(&shell) {
+            assert!(block
+                .neighbours()
+                .all(|neighbour| solid.contains(&neighbour)));
+        }
+        assert!(!shell.contains(&BlockPos::new(0, 0, 0)));
+        assert!(shell.contains(&BlockPos::new(5, 0, 0)));
+    }
+
+    #[test]
+    fn bounds_clip_without_adding_faces() {
+        let sphere = Sphere::new(Vector3::new(8.0, 8.0, 8.0), 6.0);
+        let chunk = BlockAABB::new(BlockPos::new(0, 0, 0), BlockPos::new(7, 15, 15));
+
+        let solid: Vec<BlockPos> = rasterize(&sphere, Fill::Solid)
+            .filter(|block| chunk.contains(*block))
+            .collect();
+        assert_eq!(
+            rasterize_within(&sphere, Fill::Solid, &chunk).collect::<Vec<_>>(),
+            solid
+        );
+
+        let shell: Vec<BlockPos> = rasterize(&sphere, Fill::Shell)
+            .filter(|block| chunk.contains(*block))
+            .collect();
+        assert_eq!(
+            rasterize_within(&sphere, Fill::Shell, &chunk).collect::<Vec<_>>(),
+            shell
+        );
+
+        let far = BlockAABB::new(BlockPos::new(100, 0, 0), BlockPos::new(110, 5, 5));
+        assert_eq!(rasterize_within(&sphere, Fill::Solid, &far).count(), 0);
+    }
+}
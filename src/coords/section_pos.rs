@@ -0,0 +1,127 @@
+use std::marker::PhantomData;
+
+use crate::container::AABB;
+use crate::coords::{BlockPos, ChunkDimensions, ChunkPos, Direction, LocalPos, NEIGHBOUR_OFFSETS};
+use crate::vector::Vector3;
+
+const XZ_BITS: u32 = 22;
+const Y_BITS: u32 = 20;
+
+/// Position of a chunk section, in sections
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ChunkSectionPos<D> {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    dimensions: PhantomData<D>,
+}
+
+impl<D: ChunkDimensions> ChunkSectionPos<D> {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            dimensions: PhantomData,
+        }
+    }
+
+    pub fn offset(self, x: i32, y: i32, z: i32) -> Self {
+        Self::new(self.x + x, self.y + y, self.z + z)
+    }
+
+    pub fn chunk(self) -> ChunkPos<D> {
+        ChunkPos::new(self.x, self.z)
+    }
+
+    /// block at the minimum corner of the section
+    pub fn origin(self) -> BlockPos {
+        BlockPos::new(self.x * D::WIDTH, self.y * D::HEIGHT, self.z * D::DEPTH)
+    }
+
+    pub fn block(self, local: LocalPos<D>) -> BlockPos {
+        self.origin()
+            .offset(local.x as i32, local.y as i32, local.z as i32)
+    }
+
+    pub fn aabb(self) -> AABB {
+        let min = self.origin().to_vector3();
+        let size = Vector3::new(D::WIDTH as f32, D::HEIGHT as f32, D::DEPTH as f32);
+        AABB::from_min_max(min, min + size)
+    }
+
+    pub fn center(self) -> Vector3 {
+        self.aabb().center()
+    }
+
+    /// the 6 sections sharing a face with this one
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        Direction::all().map(move |direction| self.neighbour(direction))
+    }
+
+    /// the 26 sections sharing a face, an edge or a corner with this one
+    pub fn all_neighbours(self) -> impl Iterator<Item = Self> {
+        NEIGHBOUR_OFFSETS
+            .iter()
+            .map(move |&[x, y, z]| self.offset(x, y, z))
+    }
+
+    pub fn neighbour(self, direction: Direction) -> Self {
+        let [x, y, z] = direction.offset();
+        self.offset(x, y, z)
+    }
+
+    /// Packs the position in 22 bits for x and z and 20 bits for y, positions outside of
+    /// [-2^21, 2^21) horizontally and [-2^19, 2^19) vertically wrap around
+    pub fn pack(self) -> u64 {
+        let mask = |bits: u32| (1u64 << bits) - 1;
+
+        ((self.x as u64 & mask(XZ_BITS)) << (XZ_BITS + Y_BITS))
+            | ((self.z as u64 & mask(XZ_BITS)) << Y_BITS)
+            | (self.y as u64 & mask(Y_BITS))
+    }
+
+    pub fn unpack(packed: u64) -> Self {
+        let packed = packed as i64;
+
+        // shifting left then right sign extends each field
+        Self::new(
+            (packed >> (XZ_BITS + Y_BITS)) as i32,
+            (packed << (64 - Y_BITS) >> (64 - Y_BITS)) as i32,
+            (packed << XZ_BITS >> (XZ_BITS + Y_BITS)) as i32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::Chunk16;
+
+    #[test]
+    fn pack_round_trip() {
+        let sections = [
+            (0, 0, 0),
+            (-1, -1, -1),
+            (2_097_151, 524_287, -2_097_152),
+            (-2_097_152, -524_288, 2_097_151),
+        ];
+
+        for &(x, y, z) in sections.iter() {
+            let section = ChunkSectionPos::<Chunk16>::new(x, y, z);
+            assert_eq!(ChunkSectionPos::unpack(section.pack()), section);
+        }
+    }
+
+    #[test]
+    fn bounds() {
+        let section = ChunkSectionPos::<Chunk16>::new(-1, 2, 0);
+
+        assert_eq!(section.origin(), BlockPos::new(-16, 32, 0));
+        assert_eq!(section.center(), Vector3::new(-8.0, 40.0, 8.0));
+        assert_eq!(section.neighbours().count(), 6);
+        for neighbour in section.neighbours() {
+            assert_eq!((neighbour.center() - section.center()).magnitude(), 16.0);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::geometry::Line;
 use crate::vector::Vector3;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Box {
     pub origin: Vector3,
     pub size: Vector3,
@@ -0,0 +1,176 @@
+use crate::container::AABB;
+use crate::geometry::{Segment, Triangle};
+use crate::vector::Vector3;
+
+/// Number of golden-section steps used to minimize the distance to a box, which shrinks the
+/// search interval below f32 precision
+const SEARCH_ITERATIONS: usize = 64;
+
+/// Sphere swept along a segment
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Capsule {
+    pub segment: Segment,
+    pub radius: f32,
+}
+
+impl Capsule {
+    pub fn new(start: Vector3, end: Vector3, radius: f32) -> Self {
+        Self {
+            segment: Segment::new(start, end),
+            radius,
+        }
+    }
+
+    pub fn contains(&self, point: Vector3) -> bool {
+        self.segment.distance_squared(point) <= self.radius * self.radius
+    }
+
+    /// point inside or on the surface of the capsule closest to the given point
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        let center = self.segment.closest_point(point);
+        let offset = point - center;
+        let distance = offset.magnitude();
+
+        if distance <= self.radius {
+            point
+        } else {
+            center + offset * (self.radius / distance)
+        }
+    }
+
+    pub fn aabb(&self) -> AABB {
+        let radius = Vector3::identity() * self.radius;
+
+        AABB::from_min_max(
+            Vector3::min(self.segment.start, self.segment.end) - radius,
+            Vector3::max(self.segment.start, self.segment.end) + radius,
+        )
+    }
+
+    pub fn intersects(&self, other: &Capsule) -> bool {
+        let radius = self.radius + other.radius;
+        Segment::distance(&self.segment, &other.segment) <= radius
+    }
+
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        // the distance from a point moving along the segment to a convex box is convex, so a
+        // golden-section search finds its minimum
+        let distance_squared = |t: f32| {
+            let point = self.segment.point_at(t);
+            let d = point - aabb.closest_point(point);
+            Vector3::dot(d, d)
+        };
+
+        let ratio = (5.0f32.sqrt() - 1.0) * 0.5;
+        let (mut low, mut high) = (0.0f32, 1.0f32);
+
+        for _ in 0..SEARCH_ITERATIONS {
+            let a = high - (high - low) * ratio;
+            let b = low + (high - low) * ratio;
+
+            if distance_squared(a) < distance_squared(b) {
+                high = b;
+            } else {
+                low = a;
+            }
+        }
+
+        distance_squared((low + high) * 0.5) <= self.radius * self.radius
+    }
+
+    pub fn intersects_triangle(&self, triangle: &Triangle) -> bool {
+        let (p, q) = triangle.closest_points_segment(&self.segment);
+        let d = p - q;
+        Vector3::dot(d, d) <= self.radius * self.radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{Prng, Seed};
+
+    fn random_point(prng: &mut Prng) -> Vector3 {
+        Vector3::new(
+            prng.next_f32() * 10.0 - 5.0,
+            prng.next_f32() * 10.0 - 5.0,
+            prng.next_f32() * 10.0 - 5.0,
+        )
+    }
+
+    #[test]
+    fn intersects_aabb_matches_sampling() {
+        let mut prng = Prng::new(Seed(13));
+        let aabb = AABB::from_min_max(Vector3::new(-1.0, -1.0, -1.0), Vector3::new(1.0, 2.0, 1.0));
+
+        for _ in 0..100 {
+            let capsule = Capsule::new(
+                random_point(&mut prng),
+                random_point(&mut prng),
+                prng.next_f32() * 2.0,
+            );
+
+            let sampled = (0..=2000)
+                .map(|i| {
+                    let p = capsule.segment.point_at(i as f32 / 2000.0);
+                    (p - aabb.closest_point(p)).magnitude()
+                })
+                .fold(f32::INFINITY, f32::min);
+
+            // skip grazing contacts that sampling cannot resolve
+            if (sampled - capsule.radius).abs() < 0.02 {
+                continue;
+            }
+
+            assert_eq!(capsule.intersects_aabb(&aabb), sampled <= capsule.radius);
+        }
+    }
+
+    #[test]
+    fn intersects_triangle() {
+        let triangle = Triangle::new(
+            Vector3::zero(),
+            Vector3::new(4.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 4.0),
+        );
+
+        let standing = Capsule::new(
+            Vector3::new(1.0, 0.5, 1.0),
+            Vector3::new(1.0, 2.0, 1.0),
+            0.6,
+        );
+        assert!(standing.intersects_triangle(&triangle));
+
+        let floating = Capsule::new(
+            Vector3::new(1.0, 1.0, 1.0),
+            Vector3::new(1.0, 2.0, 1.0),
+            0.6,
+        );
+        assert!(!floating.intersects_triangle(&triangle));
+
+        let beside = Capsule::new(
+            Vector3::new(2.5, -1.0, 2.5),
+            Vector3::new(2.5, 1.0, 2.5),
+            1.0,
+        );
+        assert!(beside.intersects_triangle(&triangle));
+    }
+
+    #[test]
+    fn intersects_capsule() {
+        let a = Capsule::new(Vector3::zero(), Vector3::new(0.0, 2.0, 0.0), 0.5);
+        let b = Capsule::new(
+            Vector3::new(0.9, 1.0, -1.0),
+            Vector3::new(0.9, 1.0, 1.0),
+            0.5,
+        );
+        assert!(a.intersects(&b));
+
+        let c = Capsule::new(
+            Vector3::new(1.1, 1.0, -1.0),
+            Vector3::new(1.1, 1.0, 1.0),
+            0.5,
+        );
+        assert!(!a.intersects(&c));
+    }
+}
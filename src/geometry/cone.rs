@@ -0,0 +1,56 @@
+use crate::container::AABB;
+use crate::geometry::cylinder::disc_extents;
+use crate::vector::Vector3;
+
+/// Solid cone narrowing from a circular base to its apex
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cone {
+    pub base: Vector3,
+    pub apex: Vector3,
+    /// radius of the base
+    pub radius: f32,
+}
+
+impl Cone {
+    pub fn new(base: Vector3, apex: Vector3, radius: f32) -> Self {
+        Self { base, apex, radius }
+    }
+
+    pub fn contains(&self, point: Vector3) -> bool {
+        let d = self.apex - self.base;
+        let t = Vector3::dot(point - self.base, d) / Vector3::dot(d, d);
+        if !(0.0..=1.0).contains(&t) {
+            return false;
+        }
+
+        let radial = point - (self.base + d * t);
+        let radius = self.radius * (1.0 - t);
+        Vector3::dot(radial, radial) <= radius * radius
+    }
+
+    pub fn aabb(&self) -> AABB {
+        let extents = disc_extents(self.apex - self.base, self.radius);
+
+        AABB::from_min_max(
+            Vector3::min(self.base - extents, self.apex),
+            Vector3::max(self.base + extents, self.apex),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains() {
+        let cone = Cone::new(Vector3::zero(), Vector3::new(0.0, 4.0, 0.0), 2.0);
+
+        assert!(cone.contains(Vector3::new(1.9, 0.0, 0.0)));
+        assert!(cone.contains(Vector3::new(0.9, 2.0, 0.0)));
+        assert!(!cone.contains(Vector3::new(1.1, 2.0, 0.0)));
+        assert!(!cone.contains(Vector3::new(0.0, -0.1, 0.0)));
+        assert_eq!(cone.aabb().min(), Vector3::new(-2.0, 0.0, -2.0));
+        assert_eq!(cone.aabb().max(), Vector3::new(2.0, 4.0, 2.0));
+    }
+}
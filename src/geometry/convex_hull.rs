@@ -0,0 +1,30 @@
+use crate::container::AABB;
+use crate::vector::Vector3;
+
+/// Convex shape enclosing a set of points, the points themselves do not need to be on the hull
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvexHull {
+    points: Vec<Vector3>,
+}
+
+impl ConvexHull {
+    pub fn new(points: Vec<Vector3>) -> Self {
+        assert!(!points.is_empty(), "A convex hull needs at least one point");
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[Vector3] {
+        &self.points
+    }
+
+    pub fn aabb(&self) -> AABB {
+        let (min, max) = self
+            .points
+            .iter()
+            .fold((self.points[0], self.points[0]), |(min, max), point| {
+                (Vector3::min(min, *point), Vector3::max(max, *point))
+            });
+
+        AABB::from_min_max(min, max)
+    }
+}
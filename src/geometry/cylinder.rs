@@ -0,0 +1,76 @@
+use crate::container::AABB;
+use crate::geometry::Segment;
+use crate::vector::Vector3;
+
+/// Circle of a given radius swept along a segment, with flat ends
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cylinder {
+    pub segment: Segment,
+    pub radius: f32,
+}
+
+impl Cylinder {
+    pub fn new(start: Vector3, end: Vector3, radius: f32) -> Self {
+        Self {
+            segment: Segment::new(start, end),
+            radius,
+        }
+    }
+
+    /// cylinder one unit thick centered on the plane through center
+    pub fn disc(center: Vector3, normal: Vector3, radius: f32) -> Self {
+        let half = normal.normalized() * 0.5;
+        Self::new(center - half, center + half, radius)
+    }
+
+    pub fn contains(&self, point: Vector3) -> bool {
+        let d = self.segment.direction();
+        let t = Vector3::dot(point - self.segment.start, d) / Vector3::dot(d, d);
+        if !(0.0..=1.0).contains(&t) {
+            return false;
+        }
+
+        let radial = point - self.segment.point_at(t);
+        Vector3::dot(radial, radial) <= self.radius * self.radius
+    }
+
+    pub fn aabb(&self) -> AABB {
+        let extents = disc_extents(self.segment.direction(), self.radius);
+
+        AABB::from_min_max(
+            Vector3::min(self.segment.start, self.segment.end) - extents,
+            Vector3::max(self.segment.start, self.segment.end) + extents,
+        )
+    }
+}
+
+/// half extents of the bounds of a circle perpendicular to the axis
+pub(crate) fn disc_extents(axis: Vector3, radius: f32) -> Vector3 {
+    let length_squared = Vector3::dot(axis, axis);
+    if length_squared == 0.0 {
+        return Vector3::identity() * radius;
+    }
+
+    let extent = |a: f32| radius * (1.0 - a * a / length_squared).max(0.0).sqrt();
+    Vector3::new(extent(axis.x), extent(axis.y), extent(axis.z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains() {
+        let cylinder = Cylinder::new(Vector3::zero(), Vector3::new(0.0, 4.0, 0.0), 1.0);
+
+        assert!(cylinder.contains(Vector3::new(0.9, 3.9, 0.0)));
+        assert!(!cylinder.contains(Vector3::new(0.0, 4.1, 0.0)));
+        assert!(!cylinder.contains(Vector3::new(0.8, 2.0, 0.8)));
+        assert_eq!(cylinder.aabb().min(), Vector3::new(-1.0, 0.0, -1.0));
+        assert_eq!(cylinder.aabb().max(), Vector3::new(1.0, 4.0, 1.0));
+
+        let disc = Cylinder::disc(Vector3::zero(), Vector3::right(), 2.0);
+        assert!(disc.contains(Vector3::new(0.4, 1.9, 0.0)));
+        assert!(!disc.contains(Vector3::new(0.6, 0.0, 0.0)));
+    }
+}
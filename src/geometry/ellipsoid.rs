@@ -0,0 +1,41 @@
+use crate::container::AABB;
+use crate::vector::Vector3;
+
+/// Sphere stretched along the x, y and z axes
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ellipsoid {
+    pub center: Vector3,
+    pub radii: Vector3,
+}
+
+impl Ellipsoid {
+    pub fn new(center: Vector3, radii: Vector3) -> Self {
+        Self { center, radii }
+    }
+
+    pub fn contains(&self, point: Vector3) -> bool {
+        let d = point - self.center;
+        let (x, y, z) = (d.x / self.radii.x, d.y / self.radii.y, d.z / self.radii.z);
+        x * x + y * y + z * z <= 1.0
+    }
+
+    pub fn aabb(&self) -> AABB {
+        AABB::from_center(self.center, self.radii.abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains() {
+        let ellipsoid = Ellipsoid::new(Vector3::new(1.0, 2.0, 3.0), Vector3::new(4.0, 1.0, 2.0));
+
+        assert!(ellipsoid.contains(Vector3::new(4.9, 2.0, 3.0)));
+        assert!(!ellipsoid.contains(Vector3::new(1.0, 3.1, 3.0)));
+        assert!(ellipsoid.contains(Vector3::new(3.0, 2.5, 3.0)));
+        assert!(!ellipsoid.contains(Vector3::new(3.0, 2.5, 4.5)));
+        assert_eq!(ellipsoid.aabb().min(), Vector3::new(-3.0, 1.0, 1.0));
+    }
+}
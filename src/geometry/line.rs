@@ -15,6 +15,8 @@ impl Line {
     }
 
     pub fn intersects(self, other: Line) -> bool {
-        self.contains(other.origin) || self.contains(other.origin + other.size)
+        self.contains(other.origin)
+            || self.contains(other.origin + other.size)
+            || other.contains(self.origin)
     }
 }
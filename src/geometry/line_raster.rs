@@ -0,0 +1,290 @@
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+
+/// Cells along the line between two integer positions, one per step along the axis where the
+/// line is the longest
+///
+/// Both ends are included and every cell shares at least a corner with the previous one.
+pub struct Bresenham<const N: usize> {
+    current: [i32; N],
+    step: [i32; N],
+    delta: [i64; N],
+    errors: [i64; N],
+    axis: usize,
+    /// up to 2^32 cells, more than usize holds on 32 bit targets
+    remaining: u64,
+}
+
+pub type Bresenham2 = Bresenham<2>;
+pub type Bresenham3 = Bresenham<3>;
+
+impl<const N: usize> Bresenham<N> {
+    pub fn new(start: [i32; N], end: [i32; N]) -> Self {
+        let step = std::array::from_fn(|i| end[i].cmp(&start[i]) as i32);
+        let delta: [i64; N] = std::array::from_fn(|i| (end[i] as i64 - start[i] as i64).abs());
+        let axis = (0..N).max_by_key(|&i| (delta[i], N - i)).unwrap_or(0);
+        let errors = std::array::from_fn(|i| 2 * delta[i] - delta[axis]);
+
+        Self {
+            current: start,
+            step,
+            delta,
+            errors,
+            axis,
+            remaining: delta[axis] as u64 + 1,
+        }
+    }
+}
+
+impl<const N: usize> Iterator for Bresenham<N> {
+    type Item = [i32; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let cell = self.current;
+        self.remaining -= 1;
+
+        if self.remaining > 0 {
+            let axis = self.axis;
+            self.current[axis] += self.step[axis];
+            for i in (0..N).filter(|&i| i != axis) {
+                if self.errors[i] > 0 {
+                    self.current[i] += self.step[i];
+                    self.errors[i] -= 2 * self.delta[axis];
+                }
+                self.errors[i] += 2 * self.delta[i];
+            }
+        }
+
+        Some(cell)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+/// Every cell touched by the segment between the centers of two cells
+///
+/// Cells are unit squares or cubes centered on integer positions. Consecutive cells share a
+/// face, except where the segment goes exactly through an edge or a corner, then all the
+/// cells around it are produced, closest to the start first.
+pub struct Supercover<const N: usize> {
+    current: [i32; N],
+    step: [i32; N],
+    delta: [i64; N],
+    /// boundaries crossed so far along each axis
+    crossed: [i64; N],
+    pending: VecDeque<[i32; N]>,
+    done: bool,
+}
+
+pub type Supercover2 = Supercover<2>;
+pub type Supercover3 = Supercover<3>;
+
+impl<const N: usize> Supercover<N> {
+    pub fn new(start: [i32; N], end: [i32; N]) -> Self {
+        Self {
+            current: start,
+            step: std::array::from_fn(|i| end[i].cmp(&start[i]) as i32),
+            delta: std::array::from_fn(|i| (end[i] as i64 - start[i] as i64).abs()),
+            crossed: [0; N],
+            pending: VecDeque::from(vec![start]),
+            done: false,
+        }
+    }
+
+    /// queues the cells entered at the next boundary crossing
+    fn advance(&mut self) {
+        // the k-th boundary along axis i is crossed at t = (2k + 1) / (2 * delta[i])
+        let mut axes: Vec<usize> = Vec::with_capacity(N);
+        for i in (0..N).filter(|&i| self.crossed[i] < self.delta[i]) {
+            let ordering = match axes.first() {
+                // both products can reach 2^65 with cells far apart
+                Some(&j) => ((2 * self.crossed[i] as i128 + 1) * self.delta[j] as i128)
+                    .cmp(&((2 * self.crossed[j] as i128 + 1) * self.delta[i] as i128)),
+                None => std::cmp::Ordering::Less,
+            };
+
+            match ordering {
+                std::cmp::Ordering::Less => {
+                    axes.clear();
+                    axes.push(i);
+                }
+                std::cmp::Ordering::Equal => axes.push(i),
+                std::cmp::Ordering::Greater => {}
+            }
+        }
+
+        if axes.is_empty() {
+            self.done = true;
+            return;
+        }
+
+        // crossing an edge or a corner touches every cell around it
+        let mut masks: Vec<u32> = (1..1u32 << axes.len()).collect();
+        masks.sort_by_key(|mask| mask.count_ones());
+
+        for mask in masks {
+            let mut cell = self.current;
+            for (bit, &axis) in axes.iter().enumerate() {
+                if mask & (1 << bit) != 0 {
+                    cell[axis] += self.step[axis];
+                }
+            }
+            self.pending.push_back(cell);
+        }
+
+        for &axis in axes.iter() {
+            self.current[axis] += self.step[axis];
+            self.crossed[axis] += 1;
+        }
+    }
+}
+
+impl<const N: usize> Iterator for Supercover<N> {
+    type Item = [i32; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && !self.done {
+            self.advance();
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ends() -> Vec<[i32; 3]> {
+        let mut ends = Vec::new();
+        for x in -5..=5 {
+            for y in -4..=4 {
+                for z in -3..=3 {
+                    ends.push([x, y, z]);
+                }
+            }
+        }
+        ends
+    }
+
+    /// parameters of the segment inside the box, None when it misses it
+    fn clip<const N: usize>(start: [i32; N], end: [i32; N], cell: [i32; N]) -> Option<(f64, f64)> {
+        let (mut near, mut far) = (0.0f64, 1.0f64);
+        for i in 0..N {
+            let (origin, d) = (start[i] as f64, (end[i] - start[i]) as f64);
+            let (min, max) = (cell[i] as f64 - 0.5, cell[i] as f64 + 0.5);
+
+            if d == 0.0 {
+                if origin < min || origin > max {
+                    return None;
+                }
+            } else {
+                let (a, b) = ((min - origin) / d, (max - origin) / d);
+                near = near.max(a.min(b));
+                far = far.min(a.max(b));
+            }
+        }
+        if near <= far + 1e-9 {
+            Some((near, far))
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn bresenham_in_every_octant() {
+        let start = [1, -2, 3];
+        for end in ends() {
+            let end = [start[0] + end[0], start[1] + end[1], start[2] + end[2]];
+            let cells: Vec<[i32; 3]> = Bresenham3::new(start, end).collect();
+            let longest = (0..3).map(|i| (end[i] - start[i]).abs()).max().unwrap();
+
+            assert_eq!(cells.len(), longest as usize + 1);
+            assert_eq!(cells[0], start);
+            assert_eq!(*cells.last().unwrap(), end);
+
+            for (t, (a, b)) in cells.iter().zip(cells.iter().skip(1)).enumerate() {
+                assert!((0..3).all(|i| (a[i] - b[i]).abs() <= 1));
+
+                // each cell is the closest one to the line at its step
+                for i in 0..3 {
+                    let exact = start[i] as f64
+                        + (end[i] - start[i]) as f64 * (t + 1) as f64 / longest as f64;
+                    assert!((b[i] as f64 - exact).abs() <= 0.5 + 1e-9);
+                }
+            }
+        }
+
+        let cells: Vec<[i32; 2]> = Bresenham2::new([0, 0], [4, -2]).collect();
+        assert_eq!(cells, vec![[0, 0], [1, 0], [2, -1], [3, -1], [4, -2]]);
+    }
+
+    #[test]
+    fn supercover_in_every_octant() {
+        let start = [-1, 2, 0];
+        for end in ends() {
+            let end = [start[0] + end[0], start[1] + end[1], start[2] + end[2]];
+            let cells: Vec<[i32; 3]> = Supercover3::new(start, end).collect();
+
+            assert_eq!(cells[0], start);
+            assert_eq!(*cells.last().unwrap(), end);
+
+            // every cell is touched, in order along the segment, and none is missing
+            let mut previous = 0.0;
+            for (i, cell) in cells.iter().enumerate() {
+                let (near, _) = clip(start, end, *cell).unwrap();
+                assert!(near >= previous - 1e-9);
+                previous = near;
+                assert!(!cells[i + 1..].contains(cell));
+            }
+
+            let count: i32 = (0..3).map(|i| (end[i] - start[i]).abs()).sum();
+            assert!(cells.len() > count as usize);
+            for k in 0..=100 {
+                let t = k as f64 / 100.0;
+                let cell = [0, 1, 2]
+                    .map(|i| (start[i] as f64 + (end[i] - start[i]) as f64 * t).round() as i32);
+                assert!(cells.contains(&cell));
+            }
+        }
+    }
+
+    #[test]
+    fn supercover_corners() {
+        let cells: Vec<[i32; 2]> = Supercover2::new([0, 0], [2, 2]).collect();
+        assert_eq!(
+            cells,
+            vec![[0, 0], [1, 0], [0, 1], [1, 1], [2, 1], [1, 2], [2, 2]]
+        );
+
+        let cells: Vec<[i32; 2]> = Supercover2::new([0, 0], [3, 1]).collect();
+        assert_eq!(cells, vec![[0, 0], [1, 0], [2, 0], [1, 1], [2, 1], [3, 1]]);
+
+        let cells: Vec<[i32; 2]> = Supercover2::new([0, 0], [2, 1]).collect();
+        assert_eq!(cells, vec![[0, 0], [1, 0], [1, 1], [2, 1]]);
+    }
+
+    #[test]
+    fn extreme_ends() {
+        let (start, end) = ([i32::MIN, 0], [i32::MAX, 3]);
+
+        let mut line = Bresenham2::new(start, end);
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(line.size_hint(), (1 << 32, Some(1 << 32)));
+        assert_eq!(line.next(), Some(start));
+        assert_eq!(line.next(), Some([i32::MIN + 1, 0]));
+
+        let mut cover = Supercover2::new(end, start);
+        assert_eq!(cover.next(), Some(end));
+        assert_eq!(cover.next(), Some([i32::MAX - 1, 3]));
+    }
+}
@@ -1,7 +1,29 @@
 mod r#box;
+mod capsule;
+mod cone;
+mod convex_hull;
+mod cylinder;
+mod ellipsoid;
 mod line;
+mod line_raster;
+mod ray;
 mod rect;
+mod segment;
+mod sphere;
+mod triangle;
 
+pub use self::capsule::Capsule;
+pub use self::cone::Cone;
+pub use self::convex_hull::ConvexHull;
+pub use self::cylinder::Cylinder;
+pub use self::ellipsoid::Ellipsoid;
 pub use self::line::Line;
+pub use self::line_raster::{
+    Bresenham, Bresenham2, Bresenham3, Supercover, Supercover2, Supercover3,
+};
 pub use self::r#box::Box;
+pub use self::ray::Ray;
 pub use self::rect::Rect;
+pub use self::segment::Segment;
+pub use self::sphere::Sphere;
+pub use self::triangle::Triangle;
@@ -1,7 +1,7 @@
 use crate::geometry::Line;
 use crate::vector::Vector2;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Rect {
     /// co-ordinates of a corner of the rectangle
     pub origin: Vector2,
@@ -21,6 +21,14 @@ impl Rect {
         Self::new(origin, size, size)
     }
 
+    pub fn min(&self) -> Vector2 {
+        Vector2::min(self.origin, self.origin + self.size)
+    }
+
+    pub fn max(&self) -> Vector2 {
+        Vector2::max(self.origin, self.origin + self.size)
+    }
+
     pub fn contains(&self, point: Vector2) -> bool {
         Line::new(self.origin.x, self.size.x).contains(point.x)
             && Line::new(self.origin.y, self.size.y).contains(point.y)
@@ -31,6 +39,14 @@ impl Rect {
             && Line::new(self.origin.y, self.size.y)
                 .intersects(Line::new(other.origin.y, other.size.y))
     }
+
+    /// whether the other rectangle is entirely inside this one
+    pub fn encloses(&self, other: &Rect) -> bool {
+        let (a_min, a_max) = (self.min(), self.max());
+        let (b_min, b_max) = (other.min(), other.max());
+
+        a_min.x <= b_min.x && a_min.y <= b_min.y && b_max.x <= a_max.x && b_max.y <= a_max.y
+    }
 }
 
 #[cfg(test)]
@@ -56,5 +72,15 @@ mod tests {
         assert!(square.intersects(other));
         let other = Rect::square(Vector2::new(10.0, 10.0), 5.0);
         assert!(!square.intersects(other));
+        let other = Rect::square(Vector2::new(-1.0, -1.0), 10.0);
+        assert!(square.intersects(other));
+    }
+
+    #[test]
+    fn encloses() {
+        let square = Rect::square(Vector2::new(0.0, 0.0), 5.0);
+        assert!(square.encloses(&Rect::new(Vector2::new(1.0, 1.0), 2.0, 4.0)));
+        assert!(square.encloses(&Rect::new(Vector2::new(3.0, 3.0), -3.0, -2.0)));
+        assert!(!square.encloses(&Rect::new(Vector2::new(1.0, 1.0), 2.0, 5.0)));
     }
 }
@@ -0,0 +1,169 @@
+use crate::vector::Vector3;
+
+/// Below this squared length a segment is treated as a single point
+const EPSILON: f32 = 1e-12;
+
+/// Line segment between two points in space
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub start: Vector3,
+    pub end: Vector3,
+}
+
+impl Segment {
+    pub fn new(start: Vector3, end: Vector3) -> Self {
+        Self { start, end }
+    }
+
+    /// vector going from start to end
+    pub fn direction(&self) -> Vector3 {
+        self.end - self.start
+    }
+
+    pub fn length(&self) -> f32 {
+        self.direction().magnitude()
+    }
+
+    /// t: 0.0 at start, 1.0 at end
+    pub fn point_at(&self, t: f32) -> Vector3 {
+        self.start + self.direction() * t
+    }
+
+    /// parameter in [0, 1] of the point of the segment closest to the given point
+    pub fn closest_parameter(&self, point: Vector3) -> f32 {
+        let d = self.direction();
+        let length_squared = Vector3::dot(d, d);
+
+        if length_squared <= EPSILON {
+            return 0.0;
+        }
+
+        (Vector3::dot(point - self.start, d) / length_squared).clamp(0.0, 1.0)
+    }
+
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        self.point_at(self.closest_parameter(point))
+    }
+
+    pub fn distance_squared(&self, point: Vector3) -> f32 {
+        let d = point - self.closest_point(point);
+        Vector3::dot(d, d)
+    }
+
+    /// Closest pair of points between two segments, the first one lying on a and the second on b
+    pub fn closest_points(a: &Segment, b: &Segment) -> (Vector3, Vector3) {
+        let (s, t) = Self::closest_parameters(a, b);
+        (a.point_at(s), b.point_at(t))
+    }
+
+    /// Parameters of the closest pair of points between two segments
+    pub fn closest_parameters(a: &Segment, b: &Segment) -> (f32, f32) {
+        let d1 = a.direction();
+        let d2 = b.direction();
+        let r = a.start - b.start;
+        let l1 = Vector3::dot(d1, d1);
+        let l2 = Vector3::dot(d2, d2);
+        let f = Vector3::dot(d2, r);
+
+        if l1 <= EPSILON && l2 <= EPSILON {
+            return (0.0, 0.0);
+        }
+
+        if l1 <= EPSILON {
+            return (0.0, (f / l2).clamp(0.0, 1.0));
+        }
+
+        let c = Vector3::dot(d1, r);
+
+        if l2 <= EPSILON {
+            return ((-c / l1).clamp(0.0, 1.0), 0.0);
+        }
+
+        let e = Vector3::dot(d1, d2);
+        let denominator = l1 * l2 - e * e;
+
+        // parallel segments have no unique solution, any point of a works
+        let mut s = if denominator > EPSILON {
+            ((e * f - c * l2) / denominator).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let mut t = (e * s + f) / l2;
+
+        if t < 0.0 {
+            t = 0.0;
+            s = (-c / l1).clamp(0.0, 1.0);
+        } else if t > 1.0 {
+            t = 1.0;
+            s = ((e - c) / l1).clamp(0.0, 1.0);
+        }
+
+        (s, t)
+    }
+
+    pub fn distance(a: &Segment, b: &Segment) -> f32 {
+        let (p, q) = Self::closest_points(a, b);
+        (p - q).magnitude()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{Prng, Seed};
+
+    fn random_point(prng: &mut Prng) -> Vector3 {
+        Vector3::new(
+            prng.next_f32() * 10.0 - 5.0,
+            prng.next_f32() * 10.0 - 5.0,
+            prng.next_f32() * 10.0 - 5.0,
+        )
+    }
+
+    #[test]
+    fn closest_point_matches_sampling() {
+        let mut prng = Prng::new(Seed(7));
+
+        for _ in 0..50 {
+            let segment = Segment::new(random_point(&mut prng), random_point(&mut prng));
+            let point = random_point(&mut prng);
+
+            let sampled = (0..=1000)
+                .map(|i| (segment.point_at(i as f32 / 1000.0) - point).magnitude())
+                .fold(f32::INFINITY, f32::min);
+            let distance = segment.distance_squared(point).sqrt();
+
+            assert!(distance <= sampled + 1e-4);
+            assert!(sampled - distance < 0.02);
+        }
+    }
+
+    #[test]
+    fn segment_distance_matches_sampling() {
+        let mut prng = Prng::new(Seed(11));
+
+        for _ in 0..50 {
+            let a = Segment::new(random_point(&mut prng), random_point(&mut prng));
+            let b = Segment::new(random_point(&mut prng), random_point(&mut prng));
+
+            let mut sampled = f32::INFINITY;
+            for i in 0..=1000 {
+                let p = a.point_at(i as f32 / 1000.0);
+                sampled = sampled.min(b.distance_squared(p).sqrt());
+            }
+            let distance = Segment::distance(&a, &b);
+
+            assert!(distance <= sampled + 1e-4);
+            assert!(sampled - distance < 0.02);
+        }
+    }
+
+    #[test]
+    fn parallel_segments() {
+        let a = Segment::new(Vector3::zero(), Vector3::new(2.0, 0.0, 0.0));
+        let b = Segment::new(Vector3::new(1.0, 1.0, 0.0), Vector3::new(3.0, 1.0, 0.0));
+
+        assert!((Segment::distance(&a, &b) - 1.0).abs() < 1e-6);
+    }
+}
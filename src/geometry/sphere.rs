@@ -0,0 +1,29 @@
+use crate::container::AABB;
+use crate::vector::Vector3;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sphere {
+    pub center: Vector3,
+    pub radius: f32,
+}
+
+impl Sphere {
+    pub fn new(center: Vector3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains(&self, point: Vector3) -> bool {
+        let d = point - self.center;
+        Vector3::dot(d, d) <= self.radius * self.radius
+    }
+
+    pub fn intersects(&self, other: &Sphere) -> bool {
+        let d = other.center - self.center;
+        let radius = self.radius + other.radius;
+        Vector3::dot(d, d) <= radius * radius
+    }
+
+    pub fn aabb(&self) -> AABB {
+        AABB::from_center(self.center, Vector3::identity() * self.radius)
+    }
+}
@@ -0,0 +1,289 @@
+use crate::geometry::Segment;
+use crate::vector::Vector3;
+
+/// Below this value the triangle is considered parallel to a segment
+const EPSILON: f32 = 1e-9;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Triangle {
+    pub a: Vector3,
+    pub b: Vector3,
+    pub c: Vector3,
+}
+
+impl Triangle {
+    pub fn new(a: Vector3, b: Vector3, c: Vector3) -> Self {
+        Self { a, b, c }
+    }
+
+    /// unit normal following the counter-clockwise winding a, b, c
+    pub fn normal(&self) -> Vector3 {
+        Vector3::cross(self.b - self.a, self.c - self.a).normalized()
+    }
+
+    pub fn area(&self) -> f32 {
+        Vector3::cross(self.b - self.a, self.c - self.a).magnitude() * 0.5
+    }
+
+    pub fn edges(&self) -> [Segment; 3] {
+        [
+            Segment::new(self.a, self.b),
+            Segment::new(self.b, self.c),
+            Segment::new(self.c, self.a),
+        ]
+    }
+
+    /// Weights (u, v, w) such that u * a + v * b + w * c is the projection of the point on the
+    /// plane of the triangle
+    pub fn barycentric(&self, point: Vector3) -> Vector3 {
+        let (v0, v1, v2) = (self.b - self.a, self.c - self.a, point - self.a);
+        let d00 = Vector3::dot(v0, v0);
+        let d01 = Vector3::dot(v0, v1);
+        let d11 = Vector3::dot(v1, v1);
+        let d20 = Vector3::dot(v2, v0);
+        let d21 = Vector3::dot(v2, v1);
+        let denominator = d00 * d11 - d01 * d01;
+
+        let v = (d11 * d20 - d01 * d21) / denominator;
+        let w = (d00 * d21 - d01 * d20) / denominator;
+
+        Vector3::new(1.0 - v - w, v, w)
+    }
+
+    pub fn from_barycentric(&self, weights: Vector3) -> Vector3 {
+        self.a * weights.x + self.b * weights.y + self.c * weights.z
+    }
+
+    /// Barycentric weights of the point of the triangle closest to the given point
+    pub fn closest_barycentric(&self, point: Vector3) -> Vector3 {
+        let ab = self.b - self.a;
+        let ac = self.c - self.a;
+
+        let ap = point - self.a;
+        let d1 = Vector3::dot(ab, ap);
+        let d2 = Vector3::dot(ac, ap);
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return Vector3::new(1.0, 0.0, 0.0);
+        }
+
+        let bp = point - self.b;
+        let d3 = Vector3::dot(ab, bp);
+        let d4 = Vector3::dot(ac, bp);
+        if d3 >= 0.0 && d4 <= d3 {
+            return Vector3::new(0.0, 1.0, 0.0);
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            let v = d1 / (d1 - d3);
+            return Vector3::new(1.0 - v, v, 0.0);
+        }
+
+        let cp = point - self.c;
+        let d5 = Vector3::dot(ab, cp);
+        let d6 = Vector3::dot(ac, cp);
+        if d6 >= 0.0 && d5 <= d6 {
+            return Vector3::new(0.0, 0.0, 1.0);
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            let w = d2 / (d2 - d6);
+            return Vector3::new(1.0 - w, 0.0, w);
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+            let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+            return Vector3::new(0.0, 1.0 - w, w);
+        }
+
+        let denominator = 1.0 / (va + vb + vc);
+        let v = vb * denominator;
+        let w = vc * denominator;
+
+        Vector3::new(1.0 - v - w, v, w)
+    }
+
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        self.from_barycentric(self.closest_barycentric(point))
+    }
+
+    pub fn distance_squared(&self, point: Vector3) -> f32 {
+        let d = point - self.closest_point(point);
+        Vector3::dot(d, d)
+    }
+
+    /// Point where the segment crosses the triangle, from either side
+    pub fn intersect_segment(&self, segment: &Segment) -> Option<Vector3> {
+        let ab = self.b - self.a;
+        let ac = self.c - self.a;
+        let direction = segment.direction();
+
+        let p = Vector3::cross(direction, ac);
+        let determinant = Vector3::dot(ab, p);
+        if determinant.abs() < EPSILON {
+            return None;
+        }
+
+        let inverse = 1.0 / determinant;
+        let s = segment.start - self.a;
+        let u = Vector3::dot(s, p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = Vector3::cross(s, ab);
+        let v = Vector3::dot(direction, q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = Vector3::dot(ac, q) * inverse;
+        if !(0.0..=1.0).contains(&t) {
+            return None;
+        }
+
+        Some(segment.point_at(t))
+    }
+
+    /// Closest pair of points between the triangle and a segment, the first one lying on the
+    /// segment and the second on the triangle
+    pub fn closest_points_segment(&self, segment: &Segment) -> (Vector3, Vector3) {
+        if let Some(point) = self.intersect_segment(segment) {
+            return (point, point);
+        }
+
+        let [ab, bc, ca] = self.edges();
+        let candidates = [
+            (segment.start, self.closest_point(segment.start)),
+            (segment.end, self.closest_point(segment.end)),
+            Segment::closest_points(segment, &ab),
+            Segment::closest_points(segment, &bc),
+            Segment::closest_points(segment, &ca),
+        ];
+
+        // a degenerate triangle can give NaN, which is never the closest
+        let distance = |(a, b): &(Vector3, Vector3)| Vector3::dot(*b - *a, *b - *a);
+        candidates
+            .iter()
+            .filter(|pair| !distance(pair).is_nan())
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .copied()
+            .unwrap_or(candidates[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{Prng, Seed};
+
+    fn random_point(prng: &mut Prng) -> Vector3 {
+        Vector3::new(
+            prng.next_f32() * 10.0 - 5.0,
+            prng.next_f32() * 10.0 - 5.0,
+            prng.next_f32() * 10.0 - 5.0,
+        )
+    }
+
+    fn random_triangle(prng: &mut Prng) -> Triangle {
+        Triangle::new(random_point(prng), random_point(prng), random_point(prng))
+    }
+
+    /// points spread over the whole triangle surface
+    fn sample(triangle: &Triangle, steps: usize) -> Vec<Vector3> {
+        let mut points = Vec::new();
+        for i in 0..=steps {
+            for j in 0..=steps - i {
+                let v = i as f32 / steps as f32;
+                let w = j as f32 / steps as f32;
+                points.push(triangle.from_barycentric(Vector3::new(1.0 - v - w, v, w)));
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn barycentric_round_trips() {
+        let triangle = Triangle::new(
+            Vector3::zero(),
+            Vector3::new(4.0, 0.0, 0.0),
+            Vector3::new(0.0, 4.0, 0.0),
+        );
+        let weights = triangle.barycentric(Vector3::new(1.0, 2.0, 0.0));
+
+        assert!((weights - Vector3::new(0.25, 0.25, 0.5)).magnitude() < 1e-6);
+        assert!(
+            (triangle.from_barycentric(weights) - Vector3::new(1.0, 2.0, 0.0)).magnitude() < 1e-6
+        );
+    }
+
+    #[test]
+    fn closest_point_matches_sampling() {
+        let mut prng = Prng::new(Seed(3));
+
+        for _ in 0..30 {
+            let triangle = random_triangle(&mut prng);
+            let point = random_point(&mut prng);
+
+            let sampled = sample(&triangle, 200)
+                .into_iter()
+                .map(|p| (p - point).magnitude())
+                .fold(f32::INFINITY, f32::min);
+            let distance = triangle.distance_squared(point).sqrt();
+
+            assert!(distance <= sampled + 1e-4);
+            assert!(sampled - distance < 0.05);
+        }
+    }
+
+    #[test]
+    fn segment_closest_points_match_sampling() {
+        let mut prng = Prng::new(Seed(5));
+
+        for _ in 0..30 {
+            let triangle = random_triangle(&mut prng);
+            let segment = Segment::new(random_point(&mut prng), random_point(&mut prng));
+
+            let sampled = sample(&triangle, 100)
+                .into_iter()
+                .map(|p| segment.distance_squared(p).sqrt())
+                .fold(f32::INFINITY, f32::min);
+            let (p, q) = triangle.closest_points_segment(&segment);
+            let distance = (p - q).magnitude();
+
+            assert!(distance <= sampled + 1e-4);
+            assert!(sampled - distance < 0.1);
+        }
+    }
+
+    #[test]
+    fn intersect_segment() {
+        let triangle = Triangle::new(
+            Vector3::zero(),
+            Vector3::new(4.0, 0.0, 0.0),
+            Vector3::new(0.0, 4.0, 0.0),
+        );
+
+        let crossing = Segment::new(Vector3::new(1.0, 1.0, -1.0), Vector3::new(1.0, 1.0, 1.0));
+        let point = triangle.intersect_segment(&crossing).unwrap();
+        assert!((point - Vector3::new(1.0, 1.0, 0.0)).magnitude() < 1e-6);
+
+        let short = Segment::new(Vector3::new(1.0, 1.0, -1.0), Vector3::new(1.0, 1.0, -0.5));
+        assert!(triangle.intersect_segment(&short).is_none());
+
+        let outside = Segment::new(Vector3::new(3.0, 3.0, -1.0), Vector3::new(3.0, 3.0, 1.0));
+        assert!(triangle.intersect_segment(&outside).is_none());
+    }
+
+    #[test]
+    fn degenerate_triangle() {
+        let a = Vector3::new(-2.0, 1.0, 0.0);
+        let triangle = Triangle::new(a, a, Vector3::new(0.0, 3.0, 0.0));
+        let segment = Segment::new(Vector3::new(1.0, 1.0, -1.0), Vector3::new(1.0, 1.0, 1.0));
+
+        let (on_segment, _) = triangle.closest_points_segment(&segment);
+        assert!(on_segment.x.is_finite());
+    }
+}
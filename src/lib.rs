@@ -1,4 +1,6 @@
+pub mod collision;
 pub mod container;
+pub mod coords;
 pub mod geometry;
 pub mod matrix;
 pub mod quaternion;
@@ -35,7 +35,7 @@ impl Matrix4 {
     }
 
     pub fn transposed(&self) -> Self {
-        let mut m = self.clone();
+        let mut m = *self;
         m.transpose();
         m
     }
@@ -1,5 +1,6 @@
 use std::ops;
 
+use crate::matrix::Matrix4;
 use crate::vector::Vector3;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -20,6 +21,55 @@ impl Quaternion {
         Self::new(1.0, 0.0, 0.0, 0.0)
     }
 
+    /// angle in radians, counter-clockwise around axis
+    pub fn from_axis_angle(axis: Vector3, angle: f32) -> Self {
+        let axis = axis.normalized();
+        let (sin, cos) = (angle * 0.5).sin_cos();
+
+        Self::new(cos, axis.x * sin, axis.y * sin, axis.z * sin)
+    }
+
+    /// m: matrix whose upper 3x3 block is a pure rotation
+    pub fn from_rotation_matrix(m: &Matrix4) -> Self {
+        let trace = m[0][0] + m[1][1] + m[2][2];
+
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Self::new(
+                0.25 * s,
+                (m[2][1] - m[1][2]) / s,
+                (m[0][2] - m[2][0]) / s,
+                (m[1][0] - m[0][1]) / s,
+            )
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Self::new(
+                (m[2][1] - m[1][2]) / s,
+                0.25 * s,
+                (m[0][1] + m[1][0]) / s,
+                (m[0][2] + m[2][0]) / s,
+            )
+        } else if m[1][1] > m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Self::new(
+                (m[0][2] - m[2][0]) / s,
+                (m[0][1] + m[1][0]) / s,
+                0.25 * s,
+                (m[1][2] + m[2][1]) / s,
+            )
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Self::new(
+                (m[1][0] - m[0][1]) / s,
+                (m[0][2] + m[2][0]) / s,
+                (m[1][2] + m[2][1]) / s,
+                0.25 * s,
+            )
+        };
+
+        q.normalized()
+    }
+
     pub fn conjugate(&self) -> Self {
         Self::new(self.w, -self.x, -self.y, -self.z)
     }
@@ -46,11 +96,23 @@ impl Quaternion {
         v.conjugate() * (1. / v.magnitude().powi(2))
     }
 
+    /// rotates v by this quaternion, which is expected to be normalized
+    pub fn rotate(&self, v: Vector3) -> Vector3 {
+        let u = Vector3::new(self.x, self.y, self.z);
+        let t = 2.0 * Vector3::cross(u, v);
+
+        v + self.w * t + Vector3::cross(u, t)
+    }
+
+    // unlike clamp, max and min map a NaN t to 0
+    #[allow(clippy::manual_clamp)]
     pub fn lerp(a: Self, b: Self, t: f32) -> Self {
         let t = t.max(0.).min(1.);
         ((1. - t) * a + t * b).normalized()
     }
 
+    // unlike clamp, max and min map a NaN t to 0
+    #[allow(clippy::manual_clamp)]
     pub fn slerp(a: Self, b: Self, t: f32) -> Self {
         let t = t.max(0.).min(1.);
         let theta = Quaternion::dot(a, b).acos();
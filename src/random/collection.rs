@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use crate::random::{Generator, Prng};
+
+impl<G: Generator> Prng<G> {
+    /// Fisher-Yates shuffle, every ordering is equally likely
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.next_in_range(0..=i);
+            items.swap(i, j);
+        }
+    }
+
+    /// None when there is nothing to choose from
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.next_in_range(0..items.len())])
+        }
+    }
+
+    /// Item picked with a probability proportional to its weight, None when the weights add up
+    /// to zero
+    ///
+    /// Every weight is evaluated on each call, prefer a WeightedTable to draw repeatedly from
+    /// the same items.
+    pub fn choose_weighted<'a, T, F>(&mut self, items: &'a [T], weight: F) -> Option<&'a T>
+    where
+        F: Fn(&T) -> f32,
+    {
+        let weights: Vec<f64> = items
+            .iter()
+            .map(|item| weight(item).max(0.0) as f64)
+            .collect();
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut target = self.next_f64() * total;
+        for (item, weight) in items.iter().zip(weights) {
+            if target < weight {
+                return Some(item);
+            }
+            target -= weight;
+        }
+
+        // rounding left the target past the last weight
+        items.iter().rev().find(|item| weight(item) > 0.0)
+    }
+
+    /// Up to count items of the iterator, each as likely to be kept, reading it only once
+    ///
+    /// The items are not in a random order.
+    pub fn sample_iter<T, I: IntoIterator<Item = T>>(&mut self, items: I, count: usize) -> Vec<T> {
+        let mut reservoir = Vec::with_capacity(count);
+
+        for (i, item) in items.into_iter().enumerate() {
+            if i < count {
+                reservoir.push(item);
+            } else {
+                let j = self.next_in_range(0..=i);
+                if j < count {
+                    reservoir[j] = item;
+                }
+            }
+        }
+
+        reservoir
+    }
+
+    /// count distinct indices below length, in random order
+    ///
+    /// Panics when count is above length.
+    pub fn sample_indices(&mut self, length: usize, count: usize) -> Vec<usize> {
+        assert!(count <= length, "cannot sample more indices than available");
+
+        // Fisher-Yates over 0..length, only recording the positions it moved
+        let mut moved: HashMap<usize, usize> = HashMap::new();
+        let mut indices = Vec::with_capacity(count);
+
+        for i in 0..count {
+            let j = self.next_in_range(i..length);
+            let at_i = *moved.get(&i).unwrap_or(&i);
+            let at_j = *moved.get(&j).unwrap_or(&j);
+
+            moved.insert(j, at_i);
+            indices.push(at_j);
+        }
+
+        indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Seed;
+    use std::collections::HashSet;
+
+    #[test]
+    fn pinned_sequences() {
+        let mut prng = Prng::new(Seed(197));
+
+        let mut items: Vec<u32> = (0..10).collect();
+        prng.shuffle(&mut items);
+        assert_eq!(items, vec![0, 3, 6, 8, 4, 1, 2, 9, 7, 5]);
+
+        let letters = ['a', 'b', 'c', 'd', 'e'];
+        let chosen: Vec<char> = (0..8).map(|_| *prng.choose(&letters).unwrap()).collect();
+        assert_eq!(chosen, vec!['c', 'a', 'd', 'e', 'b', 'e', 'e', 'd']);
+
+        let weighted: Vec<char> = (0..8)
+            .map(|_| {
+                *prng
+                    .choose_weighted(&letters, |c| (*c as u32 - 96) as f32)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(weighted, vec!['c', 'd', 'd', 'e', 'e', 'c', 'c', 'c']);
+
+        assert_eq!(prng.sample_iter(0..100, 5), vec![46, 48, 2, 76, 78]);
+        assert_eq!(
+            prng.sample_indices(1000, 6),
+            vec![954, 918, 676, 297, 173, 39]
+        );
+    }
+
+    #[test]
+    fn shuffles_are_uniform() {
+        let mut prng = Prng::new(Seed(199));
+        let mut counts: HashMap<Vec<u8>, u32> = HashMap::new();
+
+        for _ in 0..60_000 {
+            let mut items = [0u8, 1, 2, 3];
+            prng.shuffle(&mut items);
+            *counts.entry(items.to_vec()).or_insert(0) += 1;
+        }
+
+        // chi-squared over the 24 orderings, 23 degrees of freedom at p = 0.001
+        assert_eq!(counts.len(), 24);
+        let statistic: f64 = counts
+            .values()
+            .map(|count| (*count as f64 - 2500.0).powi(2) / 2500.0)
+            .sum();
+        assert!(statistic < 49.73);
+    }
+
+    #[test]
+    fn samples_are_distinct_and_uniform() {
+        let mut prng = Prng::new(Seed(211));
+        let mut counts = [0u32; 20];
+
+        for _ in 0..20_000 {
+            let indices = prng.sample_indices(20, 5);
+            let unique: HashSet<usize> = indices.iter().copied().collect();
+            assert_eq!(unique.len(), 5);
+
+            for index in indices {
+                counts[index] += 1;
+            }
+            for item in prng.sample_iter(0..20usize, 5) {
+                counts[item] += 1;
+            }
+        }
+
+        // 19 degrees of freedom at p = 0.001
+        let statistic: f64 = counts
+            .iter()
+            .map(|count| (*count as f64 - 10_000.0).powi(2) / 10_000.0)
+            .sum();
+        assert!(statistic < 43.82);
+
+        let mut all = prng.sample_indices(7, 7);
+        all.sort_unstable();
+        assert_eq!(all, (0..7).collect::<Vec<_>>());
+        assert_eq!(prng.sample_iter(0..3, 5), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn nothing_to_choose() {
+        let mut prng = Prng::new(Seed(223));
+        let empty: [u8; 0] = [];
+
+        assert_eq!(prng.choose(&empty), None);
+        assert_eq!(prng.choose_weighted(&[1, 2], |_| 0.0), None);
+        assert_eq!(prng.choose_weighted(&[1, 2], |x| *x as f32 - 1.0), Some(&2));
+    }
+}
@@ -0,0 +1,281 @@
+use std::f64::consts::PI;
+
+use crate::random::{Generator, Prng};
+
+/// Mean under which Poisson and binomial draws count events one by one instead of using
+/// rejection sampling
+const SMALL_MEAN: f64 = 10.0;
+
+impl<G: Generator> Prng<G> {
+    /// true with the given probability
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f64() < probability as f64
+    }
+
+    /// Normal distribution, with the Box-Muller transform
+    pub fn next_gaussian(&mut self, mean: f32, deviation: f32) -> f32 {
+        // 1 - u is never 0, so its logarithm is finite
+        let radius = (-2.0 * (1.0 - self.next_f64()).ln()).sqrt();
+        let angle = 2.0 * PI * self.next_f64();
+
+        (mean as f64 + deviation as f64 * radius * angle.cos()) as f32
+    }
+
+    /// Time between events happening rate times per unit of time on average, panics unless
+    /// rate is positive
+    pub fn next_exponential(&mut self, rate: f32) -> f32 {
+        assert!(rate > 0.0, "exponential rate {} is not positive", rate);
+        (-(1.0 - self.next_f64()).ln() / rate as f64) as f32
+    }
+
+    /// Number of events happening in an interval where mean of them are expected, 0 for a mean
+    /// up to 0, panics if the mean is not finite
+    pub fn next_poisson(&mut self, mean: f32) -> u32 {
+        assert!(mean.is_finite(), "Poisson mean {} is not finite", mean);
+        let mean = mean as f64;
+        if mean <= 0.0 {
+            return 0;
+        }
+
+        if mean < SMALL_MEAN {
+            // multiplies uniforms until their product falls below e^-mean
+            let limit = (-mean).exp();
+            let mut product = self.next_f64();
+            let mut count = 0;
+            while product > limit {
+                product *= self.next_f64();
+                count += 1;
+            }
+            return count;
+        }
+
+        // Hörmann's transformed rejection with squeeze
+        let (root, log) = (mean.sqrt(), mean.ln());
+        let b = 0.931 + 2.53 * root;
+        let a = -0.059 + 0.02483 * b;
+        let inverse_alpha = 1.1239 + 1.1328 / (b - 3.4);
+        let vr = 0.9277 - 3.6224 / (b - 2.0);
+
+        loop {
+            let u = self.next_f64() - 0.5;
+            let v = self.next_f64();
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + mean + 0.43).floor();
+
+            if us >= 0.07 && v <= vr {
+                return k as u32;
+            }
+            if k < 0.0 || (us < 0.013 && v > us) {
+                continue;
+            }
+
+            let accept = v.ln() + inverse_alpha.ln() - (a / (us * us) + b).ln();
+            if accept <= -mean + k * log - ln_factorial(k) {
+                return k as u32;
+            }
+        }
+    }
+
+    /// Number of successes among trials each succeeding with the given probability, clamped to
+    /// [0, 1], panics if the probability is not finite
+    pub fn next_binomial(&mut self, trials: u32, probability: f32) -> u32 {
+        assert!(
+            probability.is_finite(),
+            "binomial probability {} is not finite",
+            probability
+        );
+        let p = (probability as f64).clamp(0.0, 1.0);
+        if p > 0.5 {
+            return trials - self.binomial(trials, 1.0 - p);
+        }
+        self.binomial(trials, p)
+    }
+
+    /// p <= 0.5
+    fn binomial(&mut self, trials: u32, p: f64) -> u32 {
+        let n = trials as f64;
+        let q = 1.0 - p;
+
+        if n * p < SMALL_MEAN {
+            // walks the cumulative distribution from 0
+            let s = p / q;
+            let a = (n + 1.0) * s;
+            let mut r = q.powf(n);
+            let mut u = self.next_f64();
+            let mut k = 0;
+            while u > r && k < trials {
+                u -= r;
+                k += 1;
+                r *= a / k as f64 - s;
+            }
+            return k;
+        }
+
+        // Hörmann's transformed rejection
+        let spq = (n * p * q).sqrt();
+        let b = 1.15 + 2.53 * spq;
+        let a = -0.0873 + 0.0248 * b + 0.01 * p;
+        let c = n * p + 0.5;
+        let vr = 0.92 - 4.2 / b;
+        let alpha = (2.83 + 5.1 / b) * spq;
+        let lpq = (p / q).ln();
+        let m = ((n + 1.0) * p).floor();
+        let h = ln_factorial(m) + ln_factorial(n - m);
+
+        loop {
+            let u = self.next_f64() - 0.5;
+            let v = self.next_f64();
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + c).floor();
+
+            if k < 0.0 || k > n {
+                continue;
+            }
+            if us >= 0.07 && v <= vr {
+                return k as u32;
+            }
+
+            let v = (v * alpha / (a / (us * us) + b)).ln();
+            if v <= h - ln_factorial(k) - ln_factorial(n - k) + (k - m) * lpq {
+                return k as u32;
+            }
+        }
+    }
+}
+
+/// ln(k!), with Stirling's series past the first few values
+fn ln_factorial(k: f64) -> f64 {
+    const TABLE: [f64; 10] = [
+        0.0,
+        0.0,
+        std::f64::consts::LN_2,
+        1.791_759_469_228_055,
+        3.178_053_830_347_146,
+        4.787_491_742_782_046,
+        6.579_251_212_010_101,
+        8.525_161_361_065_415,
+        10.604_602_902_745_25,
+        12.801_827_480_081_469,
+    ];
+
+    if k < 10.0 {
+        return TABLE[k as usize];
+    }
+
+    let inverse = 1.0 / (k + 1.0);
+    let inverse_squared = inverse * inverse;
+    (k + 0.5) * (k + 1.0).ln() - (k + 1.0)
+        + 0.5 * (2.0 * PI).ln()
+        + inverse * (1.0 / 12.0 - inverse_squared * (1.0 / 360.0 - inverse_squared / 1260.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Seed;
+
+    fn moments(samples: &[f64]) -> (f64, f64) {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, variance)
+    }
+
+    #[test]
+    fn factorials() {
+        let mut expected = 0.0f64;
+        for k in 1..30 {
+            expected += (k as f64).ln();
+            assert!((ln_factorial(k as f64) - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn gaussian_and_exponential_moments() {
+        let mut prng = Prng::new(Seed(149));
+
+        let samples: Vec<f64> = (0..100_000)
+            .map(|_| prng.next_gaussian(3.0, 2.0) as f64)
+            .collect();
+        let (mean, variance) = moments(&samples);
+        assert!((mean - 3.0).abs() < 0.03);
+        assert!((variance - 4.0).abs() < 0.1);
+
+        let samples: Vec<f64> = (0..100_000)
+            .map(|_| prng.next_exponential(0.5) as f64)
+            .collect();
+        let (mean, variance) = moments(&samples);
+        assert!(samples.iter().all(|x| *x >= 0.0 && x.is_finite()));
+        assert!((mean - 2.0).abs() < 0.05);
+        assert!((variance - 4.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn counting_moments() {
+        let mut prng = Prng::new(Seed(151));
+
+        for &lambda in [0.5f32, 4.0, 10.0, 37.5, 1000.0].iter() {
+            let samples: Vec<f64> = (0..50_000)
+                .map(|_| prng.next_poisson(lambda) as f64)
+                .collect();
+            let (mean, variance) = moments(&samples);
+            let lambda = lambda as f64;
+            assert!((mean - lambda).abs() < 0.05 * lambda.sqrt() + 0.01);
+            assert!((variance - lambda).abs() < 0.05 * lambda + 0.02);
+        }
+
+        for &(n, p) in [(10u32, 0.3f32), (40, 0.9), (1000, 0.25), (100_000, 0.5)].iter() {
+            let samples: Vec<f64> = (0..50_000)
+                .map(|_| prng.next_binomial(n, p) as f64)
+                .collect();
+            let (mean, variance) = moments(&samples);
+            let (expected_mean, expected_variance) =
+                (n as f64 * p as f64, n as f64 * p as f64 * (1.0 - p as f64));
+            assert!(samples.iter().all(|k| *k <= n as f64));
+            assert!((mean - expected_mean).abs() < 0.05 * expected_variance.sqrt() + 0.01);
+            assert!((variance - expected_variance).abs() < 0.05 * expected_variance + 0.02);
+        }
+
+        assert_eq!(prng.next_binomial(20, 0.0), 0);
+        assert_eq!(prng.next_binomial(20, 1.0), 20);
+        assert_eq!(prng.next_poisson(0.0), 0);
+        assert_eq!(prng.next_poisson(-1.0), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_poisson_mean() {
+        Prng::new(Seed(152)).next_poisson(f32::NAN);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_binomial_probability() {
+        Prng::new(Seed(153)).next_binomial(100, f32::NAN);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_exponential_rate() {
+        Prng::new(Seed(154)).next_exponential(0.0);
+    }
+
+    #[test]
+    fn chance() {
+        let mut prng = Prng::new(Seed(157));
+        let hits = (0..100_000).filter(|_| prng.chance(0.2)).count();
+
+        assert!((hits as f64 / 100_000.0 - 0.2).abs() < 0.005);
+        assert!(!prng.chance(0.0));
+        assert!(prng.chance(1.0));
+    }
+
+    #[test]
+    fn deterministic() {
+        let (mut a, mut b) = (Prng::new(Seed(163)), Prng::new(Seed(163)));
+        for _ in 0..100 {
+            assert_eq!(a.next_poisson(50.0), b.next_poisson(50.0));
+            assert_eq!(a.next_gaussian(0.0, 1.0), b.next_gaussian(0.0, 1.0));
+        }
+    }
+}
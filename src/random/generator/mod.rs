@@ -0,0 +1,39 @@
+mod pcg;
+mod splitmix;
+mod squares;
+mod xoshiro;
+
+pub use self::pcg::{Pcg32, Pcg64};
+pub use self::splitmix::SplitMix64;
+pub use self::squares::Squares;
+pub use self::xoshiro::Xoshiro256PlusPlus;
+
+pub(crate) use self::splitmix::{mix64, GOLDEN_GAMMA};
+
+use crate::random::SeedBuffer;
+
+/// Source of random bits a Prng draws from
+///
+/// A step is one call to next_u32 or next_u64, except for Pcg32 which takes a step for each
+/// 32 bits.
+pub trait Generator: Clone {
+    /// The same seed always gives the same sequence
+    fn from_seed(seed: SeedBuffer) -> Self;
+
+    fn next_u32(&mut self) -> u32;
+
+    fn next_u64(&mut self) -> u64;
+
+    /// Skips count steps, one at a time unless the generator knows a shortcut, so by default it
+    /// takes as long as drawing them
+    fn advance(&mut self, count: u64) {
+        for _ in 0..count {
+            self.next_u32();
+        }
+    }
+
+    /// Skips far enough that generators jumped from one another do not overlap in practice
+    fn jump(&mut self) {
+        self.advance(1 << 32)
+    }
+}
@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+use crate::random::generator::{Generator, SplitMix64};
+use crate::random::SeedBuffer;
+
+const MULTIPLIER_64: u64 = 6_364_136_223_846_793_005;
+const MULTIPLIER_128: u128 = 0x2360_ed05_1fc6_5da4_4385_df64_9fcc_f645;
+
+/// O'Neill's PCG XSH RR 64/32, the same outputs as rand_pcg's Lcg64Xsh32 for the same seed
+///
+/// The default generator of Prng.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "PcgState<u64>")]
+pub struct Pcg32 {
+    state: u64,
+    /// selects the stream, always odd
+    increment: u64,
+}
+
+/// serialized form of both generators, checked before becoming one
+#[derive(Deserialize)]
+struct PcgState<T> {
+    state: T,
+    increment: T,
+}
+
+impl TryFrom<PcgState<u64>> for Pcg32 {
+    type Error = &'static str;
+
+    fn try_from(PcgState { state, increment }: PcgState<u64>) -> Result<Self, Self::Error> {
+        if increment & 1 == 0 {
+            return Err("increment has to be odd");
+        }
+        Ok(Self { state, increment })
+    }
+}
+
+impl TryFrom<PcgState<u128>> for Pcg64 {
+    type Error = &'static str;
+
+    fn try_from(PcgState { state, increment }: PcgState<u128>) -> Result<Self, Self::Error> {
+        if increment & 1 == 0 {
+            return Err("increment has to be odd");
+        }
+        Ok(Self { state, increment })
+    }
+}
+
+impl Pcg32 {
+    /// Initialized like the reference implementation's pcg32_srandom
+    pub fn new(state: u64, stream: u64) -> Self {
+        Self::from_state_increment(state, stream << 1 | 1)
+    }
+
+    fn from_state_increment(state: u64, increment: u64) -> Self {
+        let mut pcg = Self {
+            state: state.wrapping_add(increment),
+            increment,
+        };
+        pcg.step();
+        pcg
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER_64)
+            .wrapping_add(self.increment);
+    }
+}
+
+impl Generator for Pcg32 {
+    fn from_seed(seed: SeedBuffer) -> Self {
+        let [state, increment] = seed.words();
+        Self::from_state_increment(state, increment | 1)
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.step();
+
+        // xorshift of the high bits, then a rotation picked by the top 5 bits
+        let rotation = (state >> 59) as u32;
+        let shifted = (((state >> 18) ^ state) >> 27) as u32;
+        shifted.rotate_right(rotation)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32() as u64;
+        (self.next_u32() as u64) << 32 | low
+    }
+
+    /// Logarithmic time, Brown's algorithm
+    fn advance(&mut self, mut count: u64) {
+        let (mut total_multiplier, mut total_increment) = (1u64, 0u64);
+        let (mut multiplier, mut increment) = (MULTIPLIER_64, self.increment);
+
+        // composes the steps for each set bit of count
+        while count > 0 {
+            if count & 1 == 1 {
+                total_multiplier = total_multiplier.wrapping_mul(multiplier);
+                total_increment = total_increment
+                    .wrapping_mul(multiplier)
+                    .wrapping_add(increment);
+            }
+            increment = multiplier.wrapping_add(1).wrapping_mul(increment);
+            multiplier = multiplier.wrapping_mul(multiplier);
+            count >>= 1;
+        }
+
+        self.state = total_multiplier
+            .wrapping_mul(self.state)
+            .wrapping_add(total_increment);
+    }
+}
+
+/// O'Neill's PCG XSL RR 128/64, the same outputs as rand_pcg's Lcg128Xsl64
+///
+/// Slower than Pcg32 but with a period of 2^128 and 64 bits per step.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "PcgState<u128>")]
+pub struct Pcg64 {
+    state: u128,
+    /// selects the stream, always odd
+    increment: u128,
+}
+
+impl Pcg64 {
+    /// Initialized like the reference implementation's pcg64_srandom
+    pub fn new(state: u128, stream: u128) -> Self {
+        let increment = stream << 1 | 1;
+        let mut pcg = Self {
+            state: state.wrapping_add(increment),
+            increment,
+        };
+        pcg.step();
+        pcg
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER_128)
+            .wrapping_add(self.increment);
+    }
+
+    /// Same as advance, with a count as large as the period
+    pub fn advance_u128(&mut self, mut count: u128) {
+        let (mut total_multiplier, mut total_increment) = (1u128, 0u128);
+        let (mut multiplier, mut increment) = (MULTIPLIER_128, self.increment);
+
+        while count > 0 {
+            if count & 1 == 1 {
+                total_multiplier = total_multiplier.wrapping_mul(multiplier);
+                total_increment = total_increment
+                    .wrapping_mul(multiplier)
+                    .wrapping_add(increment);
+            }
+            increment = multiplier.wrapping_add(1).wrapping_mul(increment);
+            multiplier = multiplier.wrapping_mul(multiplier);
+            count >>= 1;
+        }
+
+        self.state = total_multiplier
+            .wrapping_mul(self.state)
+            .wrapping_add(total_increment);
+    }
+}
+
+impl Generator for Pcg64 {
+    fn from_seed(seed: SeedBuffer) -> Self {
+        let mut words = SplitMix64::from_seed(seed);
+        let mut next = || words.next_u64() as u128;
+
+        let state = next() << 64 | next();
+        Self::new(state, next() << 64 | next())
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.step();
+
+        // xor of the two halves, then a rotation picked by the top 6 bits
+        let rotation = (self.state >> 122) as u32;
+        let folded = (self.state >> 64) as u64 ^ self.state as u64;
+        folded.rotate_right(rotation)
+    }
+
+    fn advance(&mut self, count: u64) {
+        self.advance_u128(count as u128)
+    }
+
+    /// Skips 2^64 steps
+    fn jump(&mut self) {
+        self.advance_u128(1 << 64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::{RngCore, SeedableRng};
+    use rand_pcg::{Lcg128Xsl64, Lcg64Xsh32};
+    use serde::de::value::{Error, SeqDeserializer};
+
+    #[test]
+    fn reference_outputs() {
+        // from the demo programs of the reference implementation
+        let mut pcg32 = Pcg32::new(42, 54);
+        let outputs: Vec<u32> = (0..6).map(|_| pcg32.next_u32()).collect();
+        assert_eq!(
+            outputs,
+            vec![
+                0xa15c_02b7,
+                0x7b47_f409,
+                0xba1d_3330,
+                0x83d2_f293,
+                0xbfa4_784b,
+                0xcbed_606e
+            ]
+        );
+
+        let mut pcg64 = Pcg64::new(42, 54);
+        let outputs: Vec<u64> = (0..6).map(|_| pcg64.next_u64()).collect();
+        assert_eq!(
+            outputs,
+            vec![
+                0x86b1_da1d_7206_2b68,
+                0x1304_aa46_c985_3d39,
+                0xa367_0e9e_0dd5_0358,
+                0xf909_0e52_9a7d_ae00,
+                0xc85b_9fd8_3799_6f2c,
+                0x6061_21f8_e391_9196,
+            ]
+        );
+    }
+
+    #[test]
+    fn same_outputs_as_rand_pcg() {
+        let seed = SeedBuffer([3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3]);
+        let mut pcg = Pcg32::from_seed(seed);
+        let mut reference = Lcg64Xsh32::from_seed(seed.0);
+
+        for _ in 0..100 {
+            assert_eq!(pcg.next_u32(), reference.next_u32());
+            assert_eq!(pcg.next_u64(), reference.next_u64());
+        }
+
+        let mut pcg = Pcg64::new(1 << 100 | 17, 5);
+        let mut reference = Lcg128Xsl64::new(1 << 100 | 17, 5);
+        for _ in 0..100 {
+            assert_eq!(pcg.next_u64(), reference.next_u64());
+        }
+    }
+
+    #[test]
+    fn advancing_matches_stepping() {
+        let mut stepped = Pcg32::from_seed(SeedBuffer([7; 16]));
+        let mut advanced = stepped.clone();
+        let mut stepped_wide = Pcg64::from_seed(SeedBuffer([7; 16]));
+        let mut advanced_wide = stepped_wide.clone();
+
+        for delta in [0u64, 1, 2, 3, 100, 1234].iter() {
+            for _ in 0..*delta {
+                stepped.next_u32();
+                stepped_wide.next_u64();
+            }
+            advanced.advance(*delta);
+            advanced_wide.advance(*delta);
+            assert_eq!(stepped, advanced);
+            assert_eq!(stepped_wide, advanced_wide);
+        }
+
+        // the period is 2^64, going all the way around comes back
+        let before = advanced.clone();
+        advanced.advance(u64::MAX);
+        advanced.next_u32();
+        assert_eq!(advanced, before);
+
+        let before = advanced_wide.clone();
+        advanced_wide.advance_u128(u128::MAX);
+        advanced_wide.next_u64();
+        assert_eq!(advanced_wide, before);
+    }
+
+    #[test]
+    fn loading_an_even_increment() {
+        let load = |increment: u64| {
+            Pcg32::deserialize(SeqDeserializer::<_, Error>::new(
+                vec![7, increment].into_iter(),
+            ))
+        };
+        assert!(load(4).is_err());
+        assert_eq!(load(5).unwrap().increment, 5);
+
+        let load = |increment: u128| {
+            Pcg64::deserialize(SeqDeserializer::<_, Error>::new(
+                vec![7, increment].into_iter(),
+            ))
+        };
+        assert!(load(4).is_err());
+        assert_eq!(load(5).unwrap().increment, 5);
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+use crate::random::generator::{Generator, SplitMix64};
+use crate::random::SeedBuffer;
+
+/// Widynski's counter based Squares generator, each value only depends on the key and its index
+///
+/// Values can be read at any index without going through the ones before it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "SquaresState")]
+pub struct Squares {
+    key: u64,
+    counter: u64,
+}
+
+/// serialized form, checked before becoming a generator
+#[derive(Deserialize)]
+struct SquaresState {
+    key: u64,
+    counter: u64,
+}
+
+impl TryFrom<SquaresState> for Squares {
+    type Error = &'static str;
+
+    fn try_from(SquaresState { key, counter }: SquaresState) -> Result<Self, Self::Error> {
+        if key & 1 == 0 {
+            return Err("key has to be odd");
+        }
+        Ok(Self { key, counter })
+    }
+}
+
+impl Squares {
+    /// The key is made odd, keys with varied hexadecimal digits give the best values
+    pub fn new(key: u64) -> Self {
+        Self {
+            key: key | 1,
+            counter: 0,
+        }
+    }
+
+    /// Value returned by next_u64 at the given index, counting from 0
+    pub fn value_at(&self, index: u64) -> u64 {
+        let swap = |x: u64| x.rotate_left(32);
+
+        let y = index.wrapping_mul(self.key);
+        let z = y.wrapping_add(self.key);
+        let mut x = swap(y.wrapping_mul(y).wrapping_add(y));
+        x = swap(x.wrapping_mul(x).wrapping_add(z));
+        x = swap(x.wrapping_mul(x).wrapping_add(y));
+        let t = x.wrapping_mul(x).wrapping_add(z);
+        x = swap(t);
+
+        t ^ (x.wrapping_mul(x).wrapping_add(y) >> 32)
+    }
+
+    /// Index of the next value
+    pub fn index(&self) -> u64 {
+        self.counter
+    }
+}
+
+impl Generator for Squares {
+    fn from_seed(seed: SeedBuffer) -> Self {
+        Self::new(SplitMix64::from_seed(seed).next_u64())
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let value = self.value_at(self.counter);
+        self.counter = self.counter.wrapping_add(1);
+        value
+    }
+
+    fn advance(&mut self, count: u64) {
+        self.counter = self.counter.wrapping_add(count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::value::{Error, SeqDeserializer};
+
+    #[test]
+    fn reference_outputs() {
+        // computed by this port of the reference C code, not published test vectors
+        let mut generator = Squares::new(0xc8e4_fd15_4ce3_2f6d);
+        let outputs: Vec<u64> = (0..5).map(|_| generator.next_u64()).collect();
+
+        assert_eq!(
+            outputs,
+            vec![
+                9_226_892_942_807_635_463,
+                6_867_767_801_615_460_854,
+                17_214_866_849_759_538_642,
+                17_987_901_340_094_914_775,
+                13_074_147_593_393_247_272,
+            ]
+        );
+        assert_eq!(generator.value_at(1_000_000), 475_284_868_807_278_296);
+
+        generator.advance(999_995);
+        assert_eq!(generator.index(), 1_000_000);
+        assert_eq!(generator.next_u64(), 475_284_868_807_278_296);
+    }
+
+    #[test]
+    fn loading_an_even_key() {
+        let load = |key: u64| {
+            Squares::deserialize(SeqDeserializer::<_, Error>::new(vec![key, 5].into_iter()))
+        };
+
+        assert!(load(2).is_err());
+        assert_eq!(load(3).unwrap().index(), 5);
+    }
+}
@@ -1,6 +1,20 @@
+mod collection;
+mod distribution;
+mod generator;
 pub mod noise;
+mod poisson_disk;
 mod prng;
+mod range;
+mod sampling;
 mod seed;
+mod weighted_table;
 
+pub use self::generator::{Generator, Pcg32, Pcg64, SplitMix64, Squares, Xoshiro256PlusPlus};
+pub use self::poisson_disk::{
+    poisson_disk2, poisson_disk3, poisson_disk_tile2, poisson_disk_tile3, poisson_disk_tiles2,
+    poisson_disk_tiles3,
+};
 pub use self::prng::Prng;
-pub use self::seed::{Seed, SeedBuffer};
\ No newline at end of file
+pub use self::range::SampleRange;
+pub use self::seed::{Seed, SeedBuffer};
+pub use self::weighted_table::WeightedTable;
@@ -37,7 +37,7 @@ pub struct LayeredNoise {
 impl LayeredNoise {
     pub fn new(octaves: u32, scale: f64, persistance: f64, lacunarity: f64, seed: Seed) -> Self {
         Self {
-            noise: Perlin::new().set_seed(seed.0),
+            noise: Perlin::new().set_seed(seed.to_u32()),
             options: LayeredNoiseOptions {
                 octaves,
                 scale,
@@ -50,8 +50,8 @@ impl LayeredNoise {
 
     pub fn from_options(options: LayeredNoiseOptions) -> Self {
         Self {
-            noise: Perlin::new().set_seed(options.seed.0),
-            options: options,
+            noise: Perlin::new().set_seed(options.seed.to_u32()),
+            options,
         }
     }
 }
@@ -1,5 +1,6 @@
 mod combined;
 mod layered;
+#[allow(clippy::module_inception)]
 mod noise;
 
 pub use self::combined::CombinedNoise;
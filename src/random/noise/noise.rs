@@ -24,15 +24,15 @@ pub struct Noise {
 impl Noise {
     pub fn new(scale: f64, seed: Seed) -> Self {
         Self {
-            noise: Perlin::new().set_seed(seed.0),
+            noise: Perlin::new().set_seed(seed.to_u32()),
             options: NoiseOptions { scale, seed },
         }
     }
 
     pub fn from_options(options: NoiseOptions) -> Self {
         Self {
-            noise: Perlin::new().set_seed(options.seed.0),
-            options: options,
+            noise: Perlin::new().set_seed(options.seed.to_u32()),
+            options,
         }
     }
 }
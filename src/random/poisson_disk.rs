@@ -0,0 +1,660 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::geometry::{Box, Rect};
+use crate::random::seed::hash64;
+use crate::random::{Prng, Seed};
+use crate::vector::{Vector2, Vector3};
+
+/// Candidates tried around a point before it stops spawning new ones, Bridson's value
+const ATTEMPTS: u32 = 30;
+
+/// Cells of the first scan for room, before the radii are known
+const COARSE_SCAN_CELLS: u32 = 1024;
+
+/// Cells of the finest scan, when the radii are small next to the region
+const SCAN_CELLS: u32 = 1 << 16;
+
+/// Tells the generators of the tiles apart from the other ones derived at the same position
+const TILE_SALT: u64 = 0x0070_6f69_7373_6f6e;
+
+/// Points spread over the rectangle with Bridson's Poisson-disk sampling, no closer to one
+/// another than the larger of their radii
+///
+/// Places where the radius is not positive get no points, so a density such as a Noise can
+/// leave areas empty.
+pub fn poisson_disk2<F>(seed: Seed, region: &Rect, radius: F) -> Vec<Vector2>
+where
+    F: Fn(Vector2) -> f32,
+{
+    let (min, max) = (region.min(), region.max());
+    let mut sampler = Sampler::new(
+        [min.x, min.y],
+        [max.x, max.y],
+        f32::INFINITY,
+        |p: [f32; 2]| radius(Vector2::new(p[0], p[1])),
+    );
+
+    sampler.fill(&mut Prng::new(seed));
+    sampler
+        .points
+        .iter()
+        .map(|(p, _)| Vector2::new(p[0], p[1]))
+        .collect()
+}
+
+/// Same as poisson_disk2, in a box
+pub fn poisson_disk3<F>(seed: Seed, region: &Box, radius: F) -> Vec<Vector3>
+where
+    F: Fn(Vector3) -> f32,
+{
+    let corner = region.origin + region.size;
+    let (min, max) = (
+        Vector3::min(region.origin, corner),
+        Vector3::max(region.origin, corner),
+    );
+    let mut sampler = Sampler::new(
+        [min.x, min.y, min.z],
+        [max.x, max.y, max.z],
+        f32::INFINITY,
+        |p: [f32; 3]| radius(Vector3::new(p[0], p[1], p[2])),
+    );
+
+    sampler.fill(&mut Prng::new(seed));
+    sampler
+        .points
+        .iter()
+        .map(|(p, _)| Vector3::new(p[0], p[1], p[2]))
+        .collect()
+}
+
+/// Points of one square tile of an endless Poisson-disk sampling, the tile covering
+/// [tile * size, (tile + 1) * size)
+///
+/// A tile only depends on the seed and its position, so tiles generated in any order line up
+/// across their borders. Radii are capped at the size of the tiles. Each tile is sampled on its
+/// own and a point conflicting with a point of a neighbouring tile is left out when its
+/// priority is lower, even if that other point is left out as well, so gaps can open along the
+/// borders.
+///
+/// Deciding which points stay takes sampling the 8 neighbouring tiles too, poisson_disk_tiles2
+/// shares that work between the tiles of a batch.
+pub fn poisson_disk_tile2<F>(seed: Seed, size: f32, tile: [i64; 2], radius: F) -> Vec<Vector2>
+where
+    F: Fn(Vector2) -> f32,
+{
+    poisson_disk_tiles2(seed, size, Some(tile), radius)
+        .pop()
+        .unwrap_or_default()
+}
+
+/// Same as poisson_disk_tile2 for each of the tiles, in the same order, sampling every tile
+/// involved only once
+pub fn poisson_disk_tiles2<F, I>(seed: Seed, size: f32, tiles: I, radius: F) -> Vec<Vec<Vector2>>
+where
+    F: Fn(Vector2) -> f32,
+    I: IntoIterator<Item = [i64; 2]>,
+{
+    let mut cache = TileCache::new(seed, size, |p: [f32; 2]| radius(Vector2::new(p[0], p[1])));
+
+    tiles
+        .into_iter()
+        .map(|tile| {
+            cache
+                .points(tile)
+                .iter()
+                .map(|p| Vector2::new(p[0], p[1]))
+                .collect()
+        })
+        .collect()
+}
+
+/// Same as poisson_disk_tile2, with cubic tiles and the 26 neighbouring tiles sampled
+pub fn poisson_disk_tile3<F>(seed: Seed, size: f32, tile: [i64; 3], radius: F) -> Vec<Vector3>
+where
+    F: Fn(Vector3) -> f32,
+{
+    poisson_disk_tiles3(seed, size, Some(tile), radius)
+        .pop()
+        .unwrap_or_default()
+}
+
+/// Same as poisson_disk_tiles2, with cubic tiles
+pub fn poisson_disk_tiles3<F, I>(seed: Seed, size: f32, tiles: I, radius: F) -> Vec<Vec<Vector3>>
+where
+    F: Fn(Vector3) -> f32,
+    I: IntoIterator<Item = [i64; 3]>,
+{
+    let mut cache = TileCache::new(seed, size, |p: [f32; 3]| {
+        radius(Vector3::new(p[0], p[1], p[2]))
+    });
+
+    tiles
+        .into_iter()
+        .map(|tile| {
+            cache
+                .points(tile)
+                .iter()
+                .map(|p| Vector3::new(p[0], p[1], p[2]))
+                .collect()
+        })
+        .collect()
+}
+
+/// Dimension independent part of the sampling
+struct Sampler<F, const N: usize> {
+    min: [f32; N],
+    max: [f32; N],
+    /// radii above it are lowered to it
+    cap: f32,
+    radius: F,
+    /// grids by power of two of the radii, so radii far apart do not share cells
+    levels: BTreeMap<i32, Level<N>>,
+    points: Vec<([f32; N], f32)>,
+    smallest: f32,
+    /// distances computed by fits, a measure of the cost independent of timing
+    checks: Cell<usize>,
+}
+
+/// Points with a radius in [cell_size / 2, cell_size), and separately those with any radius
+/// below cell_size
+struct Level<const N: usize> {
+    cell_size: f32,
+    own: HashMap<[i32; N], Vec<usize>>,
+    all: HashMap<[i32; N], Vec<usize>>,
+}
+
+impl<F: Fn([f32; N]) -> f32, const N: usize> Sampler<F, N> {
+    fn new(min: [f32; N], max: [f32; N], cap: f32, radius: F) -> Self {
+        Self {
+            min,
+            max,
+            cap,
+            radius,
+            levels: BTreeMap::new(),
+            points: Vec::new(),
+            smallest: f32::INFINITY,
+            checks: Cell::new(0),
+        }
+    }
+
+    /// Scans the region for room and grows an area of points from every place found, then
+    /// scans again at the scale of the smallest radius when it is finer
+    fn fill(&mut self, prng: &mut Prng) {
+        let volume = (0..N).fold(1.0, |volume, axis| {
+            volume * (self.max[axis] - self.min[axis])
+        });
+        if volume <= 0.0 || volume.is_nan() {
+            return;
+        }
+
+        // stays coarse enough that a scan visits at most SCAN_CELLS cells
+        let finest = (volume / SCAN_CELLS as f32).powf(1.0 / N as f32);
+        let mut step = (volume / COARSE_SCAN_CELLS as f32).powf(1.0 / N as f32);
+
+        loop {
+            self.scan(prng, step);
+
+            let finer = self.smallest.max(finest);
+            if finer >= step {
+                break;
+            }
+            step = finer;
+        }
+    }
+
+    /// Tries a jittered point in each cell of a grid over the region
+    fn scan(&mut self, prng: &mut Prng, step: f32) {
+        let mut counts = [0u32; N];
+        for (axis, count) in counts.iter_mut().enumerate() {
+            *count = ((self.max[axis] - self.min[axis]) / step).ceil().max(1.0) as u32;
+        }
+
+        let mut cell = [0u32; N];
+        loop {
+            let mut point = self.min;
+            for axis in 0..N {
+                point[axis] += (cell[axis] as f32 + prng.next_f32()) * step;
+            }
+            if let Some(start) = self.try_point(point) {
+                self.grow(prng, start);
+            }
+
+            // steps through the cells like an odometer
+            let mut axis = 0;
+            while axis < N && cell[axis] + 1 == counts[axis] {
+                cell[axis] = 0;
+                axis += 1;
+            }
+            if axis == N {
+                return;
+            }
+            cell[axis] += 1;
+        }
+    }
+
+    /// Bridson's algorithm, spawning points around the active ones until none has room left
+    fn grow(&mut self, prng: &mut Prng, start: usize) {
+        let mut active = vec![start];
+
+        while !active.is_empty() {
+            let slot = prng.next_in_range(0..active.len());
+            let (center, radius) = self.points[active[slot]];
+
+            let placed = (0..ATTEMPTS).find_map(|_| self.try_point(around(prng, center, radius)));
+            match placed {
+                Some(index) => active.push(index),
+                None => {
+                    active.swap_remove(slot);
+                }
+            }
+        }
+    }
+
+    /// Index of the point when it has room
+    fn try_point(&mut self, point: [f32; N]) -> Option<usize> {
+        if !self.inside(point) {
+            return None;
+        }
+
+        let radius = self.radius_at(point)?;
+        self.add_level(level_of(radius));
+        if self.fits(point, radius) {
+            Some(self.insert(point, radius))
+        } else {
+            None
+        }
+    }
+
+    fn inside(&self, point: [f32; N]) -> bool {
+        (0..N).all(|axis| self.min[axis] <= point[axis] && point[axis] < self.max[axis])
+    }
+
+    /// None where the closure gives no usable radius, checked before the cap which would turn
+    /// NaN and infinity into the cap
+    fn radius_at(&self, point: [f32; N]) -> Option<f32> {
+        let radius = (self.radius)(point);
+        if radius > 0.0 && radius.is_finite() {
+            Some(radius.min(self.cap))
+        } else {
+            None
+        }
+    }
+
+    /// Creates the level with the points it covers, if it does not exist yet
+    fn add_level(&mut self, key: i32) {
+        if self.levels.contains_key(&key) {
+            return;
+        }
+
+        let mut level = Level {
+            cell_size: 2f32.powi(key + 1),
+            own: HashMap::new(),
+            all: HashMap::new(),
+        };
+        for (index, (point, radius)) in self.points.iter().enumerate() {
+            if level_of(*radius) < key {
+                level.all.entry(level.cell(point)).or_default().push(index);
+            }
+        }
+        self.levels.insert(key, level);
+    }
+
+    fn insert(&mut self, point: [f32; N], radius: f32) -> usize {
+        let index = self.points.len();
+        self.points.push((point, radius));
+        self.smallest = self.smallest.min(radius);
+
+        let own = level_of(radius);
+        self.add_level(own);
+        for (key, level) in self.levels.range_mut(own..) {
+            let cell = level.cell(&point);
+            if *key == own {
+                level.own.entry(cell).or_default().push(index);
+            }
+            level.all.entry(cell).or_default().push(index);
+        }
+
+        index
+    }
+
+    /// whether no point is closer than the larger of the two radii
+    fn fits(&self, point: [f32; N], radius: f32) -> bool {
+        let conflicts = |index: &usize| {
+            self.checks.set(self.checks.get() + 1);
+            let (other, other_radius) = self.points[*index];
+            distance_squared(point, other) < radius.max(other_radius).powi(2)
+        };
+
+        // the points with a radius up to the candidate's are in all of its level, the larger
+        // ones in own of the levels above, either way both radii are below the cell size
+        let own = level_of(radius);
+        self.levels.range(own..).all(|(key, level)| {
+            let cells = if *key == own { &level.all } else { &level.own };
+            let mut low = point;
+            let mut high = point;
+            for axis in 0..N {
+                low[axis] -= level.cell_size;
+                high[axis] += level.cell_size;
+            }
+            let (low, high) = (level.cell(&low), level.cell(&high));
+
+            let mut cell = low;
+            loop {
+                if let Some(indices) = cells.get(&cell) {
+                    if indices.iter().any(conflicts) {
+                        return false;
+                    }
+                }
+
+                // steps through the cells like an odometer
+                let mut axis = 0;
+                while axis < N && cell[axis] == high[axis] {
+                    cell[axis] = low[axis];
+                    axis += 1;
+                }
+                if axis == N {
+                    return true;
+                }
+                cell[axis] += 1;
+            }
+        })
+    }
+}
+
+impl<const N: usize> Level<N> {
+    fn cell(&self, point: &[f32; N]) -> [i32; N] {
+        point.map(|value| (value / self.cell_size).floor() as i32)
+    }
+}
+
+/// Key of the level of the radius, radii in [2^level, 2^(level + 1)) share a level
+fn level_of(radius: f32) -> i32 {
+    radius.log2().floor() as i32
+}
+
+fn distance_squared<const N: usize>(a: [f32; N], b: [f32; N]) -> f32 {
+    (0..N).map(|axis| (a[axis] - b[axis]).powi(2)).sum()
+}
+
+/// Uniform point of the shell between radius and twice the radius
+fn around<const N: usize>(prng: &mut Prng, center: [f32; N], radius: f32) -> [f32; N] {
+    let mut direction = [0.0; N];
+    loop {
+        for value in direction.iter_mut() {
+            *value = prng.next_f32() * 2.0 - 1.0;
+        }
+        let length = distance_squared(direction, [0.0; N]).sqrt();
+        if length > 1e-3 && length <= 1.0 {
+            direction = direction.map(|value| value / length);
+            break;
+        }
+    }
+
+    // the volume of the shell grows with the distance to the power of N
+    let scale = (1.0 + prng.next_f32() * ((1 << N) - 1) as f32).powf(1.0 / N as f32);
+    let mut point = center;
+    for (axis, value) in point.iter_mut().enumerate() {
+        *value += direction[axis] * radius * scale;
+    }
+    point
+}
+
+/// Point, radius and priority
+type RawPoint<const N: usize> = ([f32; N], f32, u64);
+
+/// Tiles sampled on their own, kept to settle the conflicts of all the tiles around them
+struct TileCache<F, const N: usize> {
+    seed: Seed,
+    size: f32,
+    radius: F,
+    raw: HashMap<[i64; N], Vec<RawPoint<N>>>,
+}
+
+impl<F: Fn([f32; N]) -> f32, const N: usize> TileCache<F, N> {
+    fn new(seed: Seed, size: f32, radius: F) -> Self {
+        assert!(size > 0.0, "The tile size has to be positive");
+
+        Self {
+            seed,
+            size,
+            radius,
+            raw: HashMap::new(),
+        }
+    }
+
+    fn raw(&mut self, tile: [i64; N]) -> &[RawPoint<N>] {
+        let (seed, size, radius) = (self.seed, self.size, &self.radius);
+
+        self.raw.entry(tile).or_insert_with(|| {
+            let min = tile.map(|coordinate| coordinate as f32 * size);
+            let max = tile.map(|coordinate| (coordinate + 1) as f32 * size);
+            let mut sampler = Sampler::new(min, max, size, radius);
+            sampler.fill(&mut Prng::for_position(seed, tile, TILE_SALT));
+
+            let start = hash64(seed.0, tile.iter().map(|coordinate| *coordinate as u64));
+            sampler
+                .points
+                .iter()
+                .enumerate()
+                .map(|(index, (point, radius))| {
+                    (*point, *radius, hash64(start, Some(index as u64)))
+                })
+                .collect()
+        })
+    }
+
+    /// Points of the tile which do not conflict with a point of higher priority around it
+    fn points(&mut self, tile: [i64; N]) -> Vec<[f32; N]> {
+        let mut neighbours = Vec::new();
+        let mut offset = [-1i64; N];
+        loop {
+            if offset.iter().any(|value| *value != 0) {
+                let mut position = tile;
+                for axis in 0..N {
+                    position[axis] += offset[axis];
+                }
+                neighbours.push(position);
+            }
+
+            let mut axis = 0;
+            while axis < N && offset[axis] == 1 {
+                offset[axis] = -1;
+                axis += 1;
+            }
+            if axis == N {
+                break;
+            }
+            offset[axis] += 1;
+        }
+
+        let mut kept: Vec<RawPoint<N>> = self.raw(tile).to_vec();
+        for position in neighbours {
+            let others = self.raw(position);
+
+            // of two conflicting points the one with the lower priority goes, ties broken by
+            // tile
+            kept.retain(|(point, radius, priority)| {
+                others.iter().all(|(other, other_radius, other_priority)| {
+                    let limit = radius.max(*other_radius);
+                    distance_squared(*point, *other) >= limit * limit
+                        || match priority.cmp(other_priority) {
+                            Ordering::Equal => tile > position,
+                            ordering => ordering == Ordering::Greater,
+                        }
+                })
+            });
+        }
+
+        kept.into_iter().map(|(point, _, _)| point).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::noise::{Noise, NoiseFn};
+
+    fn respects_radius2(points: &[Vector2], radius: &dyn Fn(Vector2) -> f32) -> bool {
+        points.iter().enumerate().all(|(i, a)| {
+            points[i + 1..]
+                .iter()
+                .all(|b| (*a - *b).magnitude() >= radius(*a).max(radius(*b)) - 1e-4)
+        })
+    }
+
+    #[test]
+    fn constant_radius() {
+        let region = Rect::new(Vector2::new(-5.0, 3.0), 20.0, 15.0);
+        let points = poisson_disk2(Seed(263), &region, |_| 1.0);
+
+        assert!(points.iter().all(|p| region.contains(*p)));
+        assert!(respects_radius2(&points, &|_| 1.0));
+        assert_eq!(points, poisson_disk2(Seed(263), &region, |_| 1.0));
+        assert_ne!(points, poisson_disk2(Seed(264), &region, |_| 1.0));
+
+        // no room is left for another point, nearly everywhere
+        let mut prng = Prng::new(Seed(269));
+        let covered = (0..1000)
+            .filter(|_| {
+                let probe = prng.point_in_rect(&region);
+                points.iter().any(|p| (*p - probe).magnitude() < 2.0)
+            })
+            .count();
+        assert!(covered > 990);
+    }
+
+    #[test]
+    fn variable_radius() {
+        let region = Rect::new(Vector2::zero(), 30.0, 10.0);
+        let radius = |p: Vector2| 0.4 + p.x / 20.0;
+        let points = poisson_disk2(Seed(271), &region, radius);
+
+        assert!(respects_radius2(&points, &radius));
+        let left = points.iter().filter(|p| p.x < 15.0).count();
+        assert!(left > 2 * (points.len() - left));
+
+        // a density from noise, nothing where it is too low
+        let noise = Noise::new(8.0, Seed(277));
+        let radius = |p: Vector2| {
+            let density = noise.get([p.x as i64, p.y as i64]) as f32;
+            if density < 0.4 {
+                0.0
+            } else {
+                0.5 / density
+            }
+        };
+        let points = poisson_disk2(Seed(281), &region, radius);
+        assert!(!points.is_empty());
+        assert!(points.iter().all(|p| radius(*p) > 0.0));
+        assert!(respects_radius2(&points, &radius));
+    }
+
+    #[test]
+    fn separate_islands() {
+        let region = Rect::new(Vector2::zero(), 30.0, 10.0);
+        let centers = [Vector2::new(4.0, 5.0), Vector2::new(26.0, 5.0)];
+        let radius = |p: Vector2| {
+            if centers.iter().any(|c| (p - *c).magnitude() < 1.5) {
+                0.5
+            } else {
+                0.0
+            }
+        };
+
+        for seed in 0..20 {
+            let points = poisson_disk2(Seed(seed), &region, radius);
+            for center in centers.iter() {
+                assert!(points.iter().any(|p| (*p - *center).magnitude() < 1.5));
+            }
+        }
+    }
+
+    #[test]
+    fn no_points_without_a_radius() {
+        let radius = |p: Vector2| if p.x < 2.0 { f32::NAN } else { f32::INFINITY };
+
+        assert!(poisson_disk_tile2(Seed(311), 4.0, [0, 0], radius).is_empty());
+        assert!(poisson_disk2(Seed(311), &Rect::square(Vector2::zero(), 4.0), radius).is_empty());
+    }
+
+    #[test]
+    fn in_a_box() {
+        let region = Box::new(Vector3::new(1.0, 2.0, 3.0), 6.0, -5.0, 7.0);
+        let points = poisson_disk3(Seed(283), &region, |_| 1.0);
+
+        assert!(points.len() > 50);
+        assert!(points.iter().all(|p| region.contains(*p)));
+        for (i, a) in points.iter().enumerate() {
+            assert!(points[i + 1..]
+                .iter()
+                .all(|b| (*a - *b).magnitude() >= 1.0 - 1e-4));
+        }
+    }
+
+    #[test]
+    fn tiles_line_up() {
+        let radius = |p: Vector2| 0.6 + (p.x * 0.3).sin().abs();
+        let mut points = Vec::new();
+
+        // generated out of order, each tile on its own
+        for &(x, y) in [(1, 0), (-1, 1), (0, 0), (0, -1), (1, 1), (0, 1), (-1, 0)].iter() {
+            let tile = poisson_disk_tile2(Seed(293), 4.0, [x, y], radius);
+            assert!(!tile.is_empty());
+            assert!(tile
+                .iter()
+                .all(|p| Rect::square(Vector2::new(x as f32, y as f32) * 4.0, 4.0).contains(*p)));
+            assert_eq!(tile, poisson_disk_tile2(Seed(293), 4.0, [x, y], radius));
+            points.extend(tile);
+        }
+
+        assert!(respects_radius2(&points, &radius));
+    }
+
+    #[test]
+    fn wide_radius_ratio() {
+        // from 0.1 on the left to 10 on the right
+        let radius = |p: [f32; 2]| 0.1 * 100f32.powf(p[0] / 20.0);
+        let mut sampler = Sampler::new([0.0; 2], [20.0; 2], f32::INFINITY, radius);
+        sampler.fill(&mut Prng::new(Seed(317)));
+
+        let points: Vec<Vector2> = sampler
+            .points
+            .iter()
+            .map(|(p, _)| Vector2::new(p[0], p[1]))
+            .collect();
+        assert!(points.len() > 2000);
+        assert!(respects_radius2(&points, &|p| radius([p.x, p.y])));
+
+        // a single grid sized by one radius ends up comparing each candidate to every point
+        assert!(sampler.checks.get() < 1500 * points.len());
+    }
+
+    #[test]
+    fn batches_of_tiles() {
+        // through the public paths
+        use crate::random::{poisson_disk_tiles2, poisson_disk_tiles3};
+
+        let radius = |p: Vector2| 0.6 + (p.x * 0.3).sin().abs();
+        let tiles = [[1, 0], [-1, 1], [0, 0], [1, 0]];
+        let batch = poisson_disk_tiles2(Seed(293), 4.0, tiles.iter().copied(), radius);
+        assert_eq!(batch.len(), 4);
+        for (tile, points) in tiles.iter().zip(batch.iter()) {
+            assert_eq!(*points, poisson_disk_tile2(Seed(293), 4.0, *tile, radius));
+        }
+
+        let tiles: Vec<[i64; 3]> = (0..8).map(|i| [i & 1, i >> 1 & 1, i >> 2]).collect();
+        let batch = poisson_disk_tiles3(Seed(307), 3.0, tiles.iter().copied(), |_| 1.0);
+        assert_eq!(
+            batch[5],
+            poisson_disk_tile3(Seed(307), 3.0, tiles[5], |_| 1.0)
+        );
+        let points: Vec<Vector3> = batch.into_iter().flatten().collect();
+        for (i, a) in points.iter().enumerate() {
+            assert!(points[i + 1..]
+                .iter()
+                .all(|b| (*a - *b).magnitude() >= 1.0 - 1e-4));
+        }
+    }
+}
@@ -1,22 +1,42 @@
-use rand_core::{RngCore, SeedableRng};
-use rand_pcg::Lcg64Xsh32;
-use std::ops::Range;
-use std::u32::MAX as U32_MAX;
-use std::u64::MAX as U64_MAX;
+use crate::random::seed::hash64;
+use crate::random::{Generator, Pcg32, SampleRange, Seed, SeedBuffer};
+use rand_core::{impls, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
 
-use crate::random::{Seed, SeedBuffer};
-
-type Rng = Lcg64Xsh32;
-
-pub struct Prng {
-    rng: Rng,
+/// Random values of all kinds, drawn from the bits of a generator
+///
+/// Saving a Prng and loading it back resumes the sequence where it was.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Prng<G = Pcg32> {
+    rng: G,
 }
 
 impl Prng {
     pub fn new(seed: Seed) -> Self {
-        Self {
-            rng: Rng::from_seed(SeedBuffer::from(seed).0),
-        }
+        Self::seeded(seed)
+    }
+
+    /// Generator only depending on the seed, the position and the salt, so that a chunk or a
+    /// feature comes out the same whatever the order things are generated in
+    ///
+    /// The salt tells apart the features generated at the same position.
+    pub fn for_position<const N: usize>(seed: Seed, position: [i64; N], salt: u64) -> Self {
+        Self::seeded_for_position(seed, position, salt)
+    }
+}
+
+impl<G: Generator> Prng<G> {
+    /// Same as Prng::new, for any generator
+    pub fn seeded(seed: Seed) -> Self {
+        Self::from_seed(SeedBuffer::from(seed))
+    }
+
+    /// Same as Prng::for_position, for any generator
+    pub fn seeded_for_position<const N: usize>(seed: Seed, position: [i64; N], salt: u64) -> Self {
+        let start = hash64(seed.0, Some(salt));
+        let hash = hash64(start, position.iter().map(|coordinate| *coordinate as u64));
+        Self::from_seed(SeedBuffer::expand(hash))
     }
 
     pub fn next_u32(&mut self) -> u32 {
@@ -27,16 +47,238 @@ impl Prng {
         self.rng.next_u64()
     }
 
+    /// uniform in [0, 1), from as many bits as the mantissa holds
     pub fn next_f32(&mut self) -> f32 {
-        self.rng.next_u32() as f32 / U32_MAX as f32
+        (self.rng.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// uniform in [0, 1]
+    pub fn next_f32_inclusive(&mut self) -> f32 {
+        (self.rng.next_u32() >> 8) as f32 / ((1u32 << 24) - 1) as f32
     }
 
+    /// uniform in [0, 1), from as many bits as the mantissa holds
     pub fn next_f64(&mut self) -> f64 {
-        self.rng.next_u64() as f64 / U64_MAX as f64
+        (self.rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// uniform in [0, 1]
+    pub fn next_f64_inclusive(&mut self) -> f64 {
+        (self.rng.next_u64() >> 11) as f64 / ((1u64 << 53) - 1) as f64
+    }
+
+    /// Skips count steps of the generator, with Pcg32 a step is a next_u32 and next_u64 counts
+    /// for two
+    ///
+    /// The PCG generators, SplitMix64 and Squares skip in logarithmic or constant time,
+    /// Xoshiro256PlusPlus draws every skipped value so large counts are slow with it.
+    pub fn advance(&mut self, count: u64) {
+        self.rng.advance(count)
+    }
+
+    /// Skips as far as the generator's jump goes, 2^32 steps with Pcg32: generators jumped from
+    /// one another do not overlap until they have drawn that many
+    pub fn jump(&mut self) {
+        self.rng.jump()
+    }
+
+    /// Independent generator for another worker, taking its seed from this one
+    pub fn split(&mut self) -> Self {
+        Self::from_seed(SeedBuffer::expand(self.next_u64()))
+    }
+
+    /// Uniform value of an integer or float range, a..b never returns b
+    ///
+    /// Panics on an empty range.
+    pub fn next_in_range<T, R: SampleRange<T>>(&mut self, range: R) -> T {
+        range.sample(self)
+    }
+}
+
+impl<G: Generator> RngCore for Prng<G> {
+    fn next_u32(&mut self) -> u32 {
+        Prng::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Prng::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl<G: Generator> SeedableRng for Prng<G> {
+    type Seed = SeedBuffer;
+
+    fn from_seed(seed: SeedBuffer) -> Self {
+        Self {
+            rng: G::from_seed(seed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{Pcg64, SplitMix64, Squares, Xoshiro256PlusPlus};
+    use serde::de::value::{Error, MapDeserializer};
+
+    fn roll<R: RngCore>(rng: &mut R) -> u32 {
+        rng.next_u32() % 6 + 1
+    }
+
+    #[test]
+    fn usable_as_rng_core() {
+        let mut a = Prng::new(Seed(137));
+        let mut b: Prng = Prng::from_seed(SeedBuffer::from(Seed(137)));
+
+        for _ in 0..100 {
+            assert_eq!(roll(&mut a), roll(&mut b));
+        }
+
+        let (mut x, mut y) = ([0u8; 13], [0u8; 13]);
+        a.fill_bytes(&mut x);
+        b.try_fill_bytes(&mut y).unwrap();
+        assert_eq!(x, y);
+        assert!(x.iter().any(|byte| *byte != 0));
+
+        let dynamic: &mut dyn RngCore = &mut a;
+        assert_eq!(dynamic.next_u64(), RngCore::next_u64(&mut b));
+    }
+
+    #[test]
+    fn seedable() {
+        let mut a: Prng = Prng::seed_from_u64(139);
+        let mut b: Prng = Prng::seed_from_u64(139);
+        let mut c: Prng = Prng::seed_from_u64(140);
+
+        let (first, second, third) = (a.next_u64(), b.next_u64(), c.next_u64());
+        assert_eq!(first, second);
+        assert_ne!(first, third);
+
+        let mut d: Prng = Prng::from_rng(&mut a).unwrap();
+        assert_ne!(d.next_u64(), a.next_u64());
+    }
+
+    #[test]
+    fn positional() {
+        let seed = Seed(233);
+        let first = |position: [i64; 3], salt| Prng::for_position(seed, position, salt).next_u64();
+
+        assert_eq!(first([4, -2, 9], 0), first([4, -2, 9], 0));
+        assert_ne!(first([4, -2, 9], 0), first([4, -2, 9], 1));
+        assert_ne!(first([4, -2, 9], 0), first([9, -2, 4], 0));
+        assert_ne!(first([1, 0, 0], 0), first([0, 1, 0], 0));
+        assert_ne!(
+            Prng::for_position(seed, [3, 5], 7).next_u64(),
+            Prng::for_position(Seed(234), [3, 5], 7).next_u64()
+        );
+
+        // neighbouring positions are not correlated: their first outputs agree on half the bits
+        let mut agreeing = 0;
+        for x in 0..64 {
+            let (a, b) = (first([x, 0, 0], 0), first([x + 1, 0, 0], 0));
+            agreeing += (!(a ^ b)).count_ones();
+        }
+        assert!((agreeing as f64 / 64.0 - 32.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn golden_values() {
+        let mut prng = Prng::new(Seed(239));
+        let outputs: Vec<u32> = (0..4).map(|_| prng.next_u32()).collect();
+        assert_eq!(outputs, vec![2724093956, 2083070388, 2897424491, 480163391]);
+        assert_eq!(prng.next_u64(), 16528807027800332593);
+
+        prng.advance(1_000_000);
+        assert_eq!(prng.next_u32(), 3820784748);
+        prng.jump();
+        assert_eq!(prng.next_u32(), 1038010348);
+
+        let mut split = prng.split();
+        assert_eq!(split.next_u32(), 727614597);
+        assert_eq!(prng.next_u32(), 1572251875);
+    }
+
+    #[test]
+    fn resumable() {
+        let mut prng = Prng::new(Seed(241));
+        prng.next_u64();
+
+        let mut saved = prng.clone();
+        let expected: Vec<u64> = (0..10).map(|_| prng.next_u64()).collect();
+        assert!(expected.iter().all(|value| *value == saved.next_u64()));
+
+        let mut skipped = prng.clone();
+        for _ in 0..5 {
+            prng.next_u32();
+        }
+        skipped.advance(5);
+        assert_eq!(prng, skipped);
+
+        // the saved form is the state of the generator, loading it gives the same values
+        let fields = vec![("state", 0x0123_4567_89ab_cdefu64), ("increment", 0x2545)];
+        let mut loaded: Prng =
+            Prng::deserialize(MapDeserializer::<_, Error>::new(fields.into_iter())).unwrap();
+        assert_eq!(loaded.next_u32(), 610837995);
+        assert_eq!(loaded.next_u32(), 1131863629);
+    }
+
+    #[test]
+    fn split_streams_differ() {
+        let mut prng = Prng::new(Seed(251));
+        let mut a = prng.split();
+        let mut b = prng.split();
+
+        let same = (0..1000).filter(|_| a.next_u32() == b.next_u32()).count();
+        assert!(same < 3);
+    }
+
+    fn check_generator<G: Generator + PartialEq + std::fmt::Debug>() {
+        let mut prng = Prng::<G>::seeded(Seed(257));
+        assert_eq!(prng, Prng::<G>::seeded(Seed(257)));
+        assert_ne!(prng, Prng::<G>::seeded(Seed(258)));
+
+        // 9 degrees of freedom at p = 0.001
+        let mut counts = [0u32; 10];
+        for _ in 0..100_000 {
+            counts[prng.next_in_range(0..10usize)] += 1;
+        }
+        let statistic: f64 = counts
+            .iter()
+            .map(|count| (*count as f64 - 10_000.0).powi(2) / 10_000.0)
+            .sum();
+        assert!(statistic < 27.88);
+
+        let mut skipped = prng.clone();
+        for _ in 0..10 {
+            prng.next_u32();
+        }
+        skipped.advance(10);
+        assert_eq!(prng, skipped);
+
+        let mut jumped = prng.clone();
+        jumped.jump();
+        assert_ne!(jumped.next_u64(), prng.next_u64());
+        assert_ne!(
+            Prng::<G>::seeded_for_position(Seed(257), [1, 2], 0).next_u64(),
+            Prng::<G>::seeded_for_position(Seed(257), [2, 1], 0).next_u64()
+        );
     }
 
-    pub fn next_in_range(&mut self, range: Range<usize>) -> usize {
-        let height = range.end - range.start;
-        (self.next_f32() * height as f32 + range.start as f32) as usize
+    #[test]
+    fn every_generator() {
+        check_generator::<Pcg32>();
+        check_generator::<Pcg64>();
+        check_generator::<SplitMix64>();
+        check_generator::<Squares>();
+        check_generator::<Xoshiro256PlusPlus>();
     }
 }
@@ -0,0 +1,231 @@
+use std::ops::{Range, RangeInclusive};
+
+use crate::random::{Generator, Prng};
+
+/// Range a value can be uniformly drawn from
+pub trait SampleRange<T> {
+    fn sample<G: Generator>(self, prng: &mut Prng<G>) -> T;
+}
+
+/// Uniform value below bound, Lemire's multiply and reject method
+fn below_u32<G: Generator>(prng: &mut Prng<G>, bound: u32) -> u32 {
+    let mut m = prng.next_u32() as u64 * bound as u64;
+
+    // the low part falls below the threshold for the values which would be picked once more
+    // than the others
+    if (m as u32) < bound {
+        let threshold = bound.wrapping_neg() % bound;
+        while (m as u32) < threshold {
+            m = prng.next_u32() as u64 * bound as u64;
+        }
+    }
+
+    (m >> 32) as u32
+}
+
+fn below_u64<G: Generator>(prng: &mut Prng<G>, bound: u64) -> u64 {
+    let mut m = prng.next_u64() as u128 * bound as u128;
+
+    if (m as u64) < bound {
+        let threshold = bound.wrapping_neg() % bound;
+        while (m as u64) < threshold {
+            m = prng.next_u64() as u128 * bound as u128;
+        }
+    }
+
+    (m >> 64) as u64
+}
+
+macro_rules! impl_integer_range {
+    ($ty:ty, $unsigned:ty, $large:ty, $below:ident, $next:ident) => {
+        impl SampleRange<$ty> for Range<$ty> {
+            fn sample<G: Generator>(self, prng: &mut Prng<G>) -> $ty {
+                assert!(self.start < self.end, "cannot sample an empty range");
+
+                let width = self.end.wrapping_sub(self.start) as $unsigned as $large;
+                self.start.wrapping_add($below(prng, width) as $ty)
+            }
+        }
+
+        impl SampleRange<$ty> for RangeInclusive<$ty> {
+            fn sample<G: Generator>(self, prng: &mut Prng<G>) -> $ty {
+                let (start, end) = self.into_inner();
+                assert!(start <= end, "cannot sample an empty range");
+
+                // the width only overflows when the range covers every value
+                let width = (end.wrapping_sub(start) as $unsigned as $large).wrapping_add(1);
+                if width == 0 {
+                    prng.$next() as $ty
+                } else {
+                    start.wrapping_add($below(prng, width) as $ty)
+                }
+            }
+        }
+    };
+}
+
+impl_integer_range!(u8, u8, u32, below_u32, next_u32);
+impl_integer_range!(u16, u16, u32, below_u32, next_u32);
+impl_integer_range!(u32, u32, u32, below_u32, next_u32);
+impl_integer_range!(u64, u64, u64, below_u64, next_u64);
+impl_integer_range!(i8, u8, u32, below_u32, next_u32);
+impl_integer_range!(i16, u16, u32, below_u32, next_u32);
+impl_integer_range!(i32, u32, u32, below_u32, next_u32);
+impl_integer_range!(i64, u64, u64, below_u64, next_u64);
+impl_integer_range!(usize, usize, u64, below_u64, next_u64);
+impl_integer_range!(isize, usize, u64, below_u64, next_u64);
+
+macro_rules! impl_float_range {
+    ($ty:ty, $next:ident, $next_inclusive:ident) => {
+        impl SampleRange<$ty> for Range<$ty> {
+            /// never returns the end, even when rounding would
+            fn sample<G: Generator>(self, prng: &mut Prng<G>) -> $ty {
+                assert!(self.start < self.end, "cannot sample an empty range");
+                assert!(
+                    self.start.is_finite() && self.end.is_finite(),
+                    "cannot sample an infinite range"
+                );
+
+                // interpolating never overflows, even when the width is past the largest float
+                loop {
+                    let u = prng.$next();
+                    let value = self.start * (1.0 - u) + self.end * u;
+                    if value < self.end {
+                        return value.max(self.start);
+                    }
+                }
+            }
+        }
+
+        impl SampleRange<$ty> for RangeInclusive<$ty> {
+            fn sample<G: Generator>(self, prng: &mut Prng<G>) -> $ty {
+                let (start, end) = self.into_inner();
+                assert!(start <= end, "cannot sample an empty range");
+                assert!(
+                    start.is_finite() && end.is_finite(),
+                    "cannot sample an infinite range"
+                );
+
+                let u = prng.$next_inclusive();
+                (start * (1.0 - u) + end * u).max(start).min(end)
+            }
+        }
+    };
+}
+
+impl_float_range!(f32, next_f32, next_f32_inclusive);
+impl_float_range!(f64, next_f64, next_f64_inclusive);
+
+#[cfg(test)]
+mod tests {
+    use crate::random::{Prng, Seed};
+
+    /// Pearson's statistic of the counts against a uniform distribution
+    fn chi_squared(counts: &[u32]) -> f64 {
+        let total: u32 = counts.iter().sum();
+        let expected = total as f64 / counts.len() as f64;
+
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn integers_are_uniform() {
+        let mut prng = Prng::new(Seed(109));
+
+        // critical values at p = 0.001 for 9 and 255 degrees of freedom
+        let mut counts = [0; 10];
+        for _ in 0..100_000 {
+            counts[prng.next_in_range(0..10usize)] += 1;
+        }
+        assert!(chi_squared(&counts) < 27.88);
+
+        let mut counts = [0; 256];
+        for _ in 0..256_000 {
+            counts[prng.next_in_range(0..=255u8) as usize] += 1;
+        }
+        assert!(chi_squared(&counts) < 330.52);
+
+        let mut counts = [0; 10];
+        for _ in 0..100_000 {
+            counts[(prng.next_in_range(-5..=4i64) + 5) as usize] += 1;
+        }
+        assert!(chi_squared(&counts) < 27.88);
+    }
+
+    #[test]
+    fn large_ranges_are_uniform() {
+        let mut prng = Prng::new(Seed(113));
+
+        // a width of 3 * 2^30 is where scaling a 32 bit float is the most biased
+        let width = 3u32 << 30;
+        let mut counts = [0; 3];
+        for _ in 0..90_000 {
+            counts[(prng.next_in_range(0..width) >> 30) as usize] += 1;
+        }
+        assert!(chi_squared(&counts) < 13.82);
+
+        let mut counts = [0; 2];
+        for _ in 0..10_000 {
+            let value = prng.next_in_range(i64::MIN..=i64::MAX);
+            counts[(value < 0) as usize] += 1;
+        }
+        assert!(chi_squared(&counts) < 10.83);
+
+        assert_eq!(prng.next_in_range(7..8u64), 7);
+        assert_eq!(prng.next_in_range(u8::MAX..=u8::MAX), u8::MAX);
+    }
+
+    #[test]
+    fn floats_are_uniform_and_exclude_the_end() {
+        let mut prng = Prng::new(Seed(127));
+
+        let mut counts = [0; 20];
+        for _ in 0..100_000 {
+            let value = prng.next_in_range(-1.0..1.0f32);
+            assert!((-1.0..1.0).contains(&value));
+            counts[((value + 1.0) * 10.0) as usize] += 1;
+        }
+        assert!(chi_squared(&counts) < 43.82);
+
+        let end = 1.0f32 + f32::EPSILON;
+        for _ in 0..1000 {
+            assert_eq!(prng.next_in_range(1.0..end), 1.0);
+            assert!((0.0..=1.0).contains(&prng.next_in_range(0.0..=1.0f64)));
+        }
+
+        for _ in 0..100_000 {
+            assert!(prng.next_f32() < 1.0);
+            assert!(prng.next_f64() < 1.0);
+        }
+    }
+
+    #[test]
+    fn widest_float_ranges() {
+        let mut prng = Prng::new(Seed(131));
+        let mut negative = 0;
+
+        for _ in 0..1000 {
+            let value = prng.next_in_range(f32::MIN..f32::MAX);
+            assert!(value.is_finite() && value < f32::MAX);
+            negative += (value < 0.0) as u32;
+
+            assert!(prng.next_in_range(f64::MIN..=f64::MAX).is_finite());
+        }
+        assert!(negative > 400 && negative < 600);
+    }
+
+    #[test]
+    #[should_panic]
+    fn infinite_range() {
+        Prng::new(Seed(131)).next_in_range(0.0..f32::INFINITY);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_range() {
+        Prng::new(Seed(131)).next_in_range(3..3i32);
+    }
+}
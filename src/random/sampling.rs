@@ -0,0 +1,189 @@
+use std::f32::consts::PI;
+
+use crate::geometry::{Box, Rect, Sphere, Triangle};
+use crate::quaternion::Quaternion;
+use crate::random::{Generator, Prng};
+use crate::vector::{Vector2, Vector3};
+
+impl<G: Generator> Prng<G> {
+    pub fn point_in_rect(&mut self, rect: &Rect) -> Vector2 {
+        let (x, y) = (self.next_f32(), self.next_f32());
+        rect.origin + Vector2::new(rect.size.x * x, rect.size.y * y)
+    }
+
+    pub fn point_in_box(&mut self, volume: &Box) -> Vector3 {
+        let (x, y, z) = (self.next_f32(), self.next_f32(), self.next_f32());
+        volume.origin + Vector3::new(volume.size.x * x, volume.size.y * y, volume.size.z * z)
+    }
+
+    pub fn point_in_disc(&mut self, center: Vector2, radius: f32) -> Vector2 {
+        // the square root keeps the density uniform as the circumference grows
+        let distance = radius * self.next_f32().sqrt();
+        center + self.unit_vector2() * distance
+    }
+
+    pub fn point_in_sphere(&mut self, sphere: &Sphere) -> Vector3 {
+        let distance = sphere.radius * self.next_f32().cbrt();
+        sphere.center + self.unit_vector3() * distance
+    }
+
+    pub fn point_on_sphere(&mut self, sphere: &Sphere) -> Vector3 {
+        sphere.center + self.unit_vector3() * sphere.radius
+    }
+
+    pub fn point_on_triangle(&mut self, triangle: &Triangle) -> Vector3 {
+        let (u, v) = (self.next_f32().sqrt(), self.next_f32());
+        triangle.a * (1.0 - u) + triangle.b * (u * (1.0 - v)) + triangle.c * (u * v)
+    }
+
+    pub fn unit_vector2(&mut self) -> Vector2 {
+        let (sin, cos) = (2.0 * PI * self.next_f32()).sin_cos();
+        Vector2::new(cos, sin)
+    }
+
+    pub fn unit_vector3(&mut self) -> Vector3 {
+        let z = 2.0 * self.next_f32() - 1.0;
+        let radius = (1.0 - z * z).max(0.0).sqrt();
+        let (sin, cos) = (2.0 * PI * self.next_f32()).sin_cos();
+        Vector3::new(radius * cos, radius * sin, z)
+    }
+
+    /// Direction on the side of the normal, more likely the closer it is to the normal as
+    /// diffuse lighting expects
+    pub fn cosine_hemisphere(&mut self, normal: Vector3) -> Vector3 {
+        let normal = normal.normalized();
+        let helper = if normal.x.abs() > 0.9 {
+            Vector3::up()
+        } else {
+            Vector3::right()
+        };
+        let tangent = Vector3::cross(helper, normal).normalized();
+        let bitangent = Vector3::cross(normal, tangent);
+
+        // points of the unit disc projected up onto the hemisphere
+        let u = self.next_f32();
+        let disc = self.unit_vector2() * u.sqrt();
+        tangent * disc.x + bitangent * disc.y + normal * (1.0 - u).max(0.0).sqrt()
+    }
+
+    /// Uniformly distributed rotation, from Shoemake's method
+    pub fn rotation(&mut self) -> Quaternion {
+        let u = self.next_f32();
+        let (a, b) = ((1.0 - u).sqrt(), u.sqrt());
+        let (sin1, cos1) = (2.0 * PI * self.next_f32()).sin_cos();
+        let (sin2, cos2) = (2.0 * PI * self.next_f32()).sin_cos();
+
+        Quaternion::new(b * cos2, a * sin1, a * cos1, b * sin2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Seed;
+
+    const SAMPLES: usize = 50_000;
+
+    fn mean3(points: &[Vector3]) -> Vector3 {
+        points.iter().fold(Vector3::zero(), |acc, p| {
+            acc + *p * (1.0 / points.len() as f32)
+        })
+    }
+
+    #[test]
+    fn points_in_shapes() {
+        let mut prng = Prng::new(Seed(179));
+
+        let rect = Rect::new(Vector2::new(1.0, -2.0), -3.0, 4.0);
+        let volume = Box::new(Vector3::new(0.0, 1.0, 2.0), 1.0, 2.0, 3.0);
+        for _ in 0..1000 {
+            assert!(rect.contains(prng.point_in_rect(&rect)));
+            assert!(volume.contains(prng.point_in_box(&volume)));
+        }
+
+        let center = Vector2::new(4.0, 5.0);
+        let inner = (0..SAMPLES)
+            .map(|_| prng.point_in_disc(center, 2.0) - center)
+            .inspect(|p| assert!(p.magnitude() <= 2.0 + 1e-5))
+            .filter(|p| p.magnitude() < 1.0)
+            .count();
+        assert!((inner as f32 / SAMPLES as f32 - 0.25).abs() < 0.01);
+
+        let sphere = Sphere::new(Vector3::new(1.0, 2.0, 3.0), 2.0);
+        let points: Vec<Vector3> = (0..SAMPLES)
+            .map(|_| prng.point_in_sphere(&sphere))
+            .collect();
+        let inner = points
+            .iter()
+            .filter(|p| (**p - sphere.center).magnitude() < 1.0)
+            .count();
+        assert!(points
+            .iter()
+            .all(|p| (*p - sphere.center).magnitude() <= 2.0 + 1e-5));
+        assert!((inner as f32 / SAMPLES as f32 - 0.125).abs() < 0.01);
+        assert!((mean3(&points) - sphere.center).magnitude() < 0.03);
+
+        for _ in 0..1000 {
+            let p = prng.point_on_sphere(&sphere);
+            assert!(((p - sphere.center).magnitude() - 2.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn points_on_triangle() {
+        let mut prng = Prng::new(Seed(181));
+        let triangle = Triangle::new(
+            Vector3::zero(),
+            Vector3::new(4.0, 0.0, 0.0),
+            Vector3::new(0.0, 6.0, 3.0),
+        );
+
+        let points: Vec<Vector3> = (0..SAMPLES)
+            .map(|_| prng.point_on_triangle(&triangle))
+            .collect();
+        for p in points.iter().take(1000) {
+            let weights = triangle.barycentric(*p);
+            assert!(weights.x >= -1e-4 && weights.y >= -1e-4 && weights.z >= -1e-4);
+        }
+
+        let centroid = (triangle.a + triangle.b + triangle.c) * (1.0 / 3.0);
+        assert!((mean3(&points) - centroid).magnitude() < 0.03);
+    }
+
+    #[test]
+    fn directions() {
+        let mut prng = Prng::new(Seed(191));
+
+        let directions: Vec<Vector3> = (0..SAMPLES).map(|_| prng.unit_vector3()).collect();
+        assert!(directions
+            .iter()
+            .all(|d| (d.magnitude() - 1.0).abs() < 1e-5));
+        assert!(mean3(&directions).magnitude() < 0.02);
+
+        let sum = (0..SAMPLES).fold(Vector2::zero(), |acc, _| acc + prng.unit_vector2());
+        assert!(sum.magnitude() / (SAMPLES as f32) < 0.02);
+
+        // the cosine of the angle to the normal averages 2/3
+        let normal = Vector3::new(1.0, 1.0, 0.0).normalized();
+        let cosines: Vec<f32> = (0..SAMPLES)
+            .map(|_| Vector3::dot(prng.cosine_hemisphere(normal), normal))
+            .collect();
+        assert!(cosines.iter().all(|c| *c >= -1e-5));
+        let mean = cosines.iter().sum::<f32>() / SAMPLES as f32;
+        assert!((mean - 2.0 / 3.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn rotations() {
+        let mut prng = Prng::new(Seed(193));
+        let rotated: Vec<Vector3> = (0..SAMPLES)
+            .map(|_| {
+                let rotation = prng.rotation();
+                assert!((rotation.magnitude() - 1.0).abs() < 1e-4);
+                rotation.rotate(Vector3::forward())
+            })
+            .collect();
+
+        assert!(mean3(&rotated).magnitude() < 0.02);
+    }
+}
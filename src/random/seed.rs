@@ -1,35 +1,192 @@
 use serde::{Deserialize, Serialize};
-use std::convert::From;
-use std::iter;
+use std::collections::hash_map::RandomState;
+use std::convert::{From, Infallible};
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::SystemTime;
 
-#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Seed(pub u32);
+use crate::random::generator::{mix64, Generator, SplitMix64, GOLDEN_GAMMA};
+
+/// Hash of the values in order, distinct orders give unrelated hashes
+pub(crate) fn hash64<I: IntoIterator<Item = u64>>(start: u64, values: I) -> u64 {
+    values.into_iter().fold(mix64(start), |hash, value| {
+        mix64(hash.wrapping_add(GOLDEN_GAMMA) ^ value)
+    })
+}
+
+/// Seeds handed out by this process, so that two calls in the same instant still differ
+static CREATED: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Seed(pub u64);
 
 impl Seed {
+    /// Unpredictable seed, mixing the time with the randomness the standard library gets from
+    /// the operating system
     pub fn new() -> Self {
-        let value = SystemTime::now()
+        let time = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_millis();
-        Seed((value & 0xffffffff) as u32)
+            .unwrap_or_default()
+            .as_nanos();
+
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(time);
+        hasher.write_u64(CREATED.fetch_add(1, Ordering::Relaxed));
+        hasher.write_u32(std::process::id());
+
+        Seed(mix64(hasher.finish()))
+    }
+
+    /// Independent seed for a part of the world, the same salt always derives the same seed
+    pub fn derive(&self, salt: u64) -> Seed {
+        Seed(hash64(self.0, Some(salt)))
+    }
+
+    /// 32 bit seed for the noise functions, seeds below 2^32 keep their value
+    pub fn to_u32(&self) -> u32 {
+        (self.0 ^ (self.0 >> 32)) as u32
+    }
+}
+
+/// Decimal value, parsed back by from_str
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Numbers are taken as they are, negative ones included, any other text is hashed so that
+/// players can name their world's seed
+impl FromStr for Seed {
+    type Err = Infallible;
+
+    fn from_str(text: &str) -> Result<Self, Infallible> {
+        let text = text.trim();
+
+        if let Ok(value) = text.parse::<u64>() {
+            return Ok(Seed(value));
+        }
+        if let Ok(value) = text.parse::<i64>() {
+            return Ok(Seed(value as u64));
+        }
+
+        let words = text.as_bytes().chunks(8).map(|chunk| {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(word)
+        });
+        Ok(Seed(hash64(text.len() as u64, words)))
     }
 }
 
 #[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub struct SeedBuffer(pub [u8; 16]);
 
+impl SeedBuffer {
+    /// Fills the buffer from the SplitMix64 sequence starting at value
+    pub(crate) fn expand(value: u64) -> Self {
+        let mut buffer = [0; 16];
+        let mut generator = SplitMix64::new(value);
+
+        for chunk in buffer.chunks_mut(8) {
+            chunk.copy_from_slice(&generator.next_u64().to_le_bytes());
+        }
+
+        Self(buffer)
+    }
+
+    /// The two little endian halves of the buffer
+    pub(crate) fn words(&self) -> [u64; 2] {
+        let mut words = [0; 2];
+        for (word, bytes) in words.iter_mut().zip(self.0.chunks(8)) {
+            let mut buffer = [0; 8];
+            buffer.copy_from_slice(bytes);
+            *word = u64::from_le_bytes(buffer);
+        }
+        words
+    }
+}
+
+/// The seed repeated four times, big endian, which is what worlds have always been generated
+/// from: seeds below 2^32 keep their sequence, the high half of larger ones is folded into
+/// every other word
 impl From<Seed> for SeedBuffer {
     fn from(seed: Seed) -> Self {
+        let (low, high) = (seed.0 as u32, (seed.0 >> 32) as u32);
         let mut buffer: [u8; 16] = [0; 16];
 
-        for (i, value) in iter::repeat(seed.0).take(4).enumerate() {
-            buffer[i * 4] = (value >> 24 & 0xff) as u8;
-            buffer[i * 4 + 1] = (value >> 16 & 0xff) as u8;
-            buffer[i * 4 + 2] = (value >> 8 & 0xff) as u8;
-            buffer[i * 4 + 3] = (value & 0xff) as u8;
+        for (i, value) in [low, low ^ high, low, low ^ high].iter().enumerate() {
+            buffer[i * 4..i * 4 + 4].copy_from_slice(&value.to_be_bytes());
         }
 
         Self(buffer)
     }
 }
+
+impl AsMut<[u8]> for SeedBuffer {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_seeds() {
+        let seed = Seed(229);
+
+        assert_eq!(seed.derive(1), seed.derive(1));
+        assert_ne!(seed.derive(1), seed.derive(2));
+        assert_ne!(seed.derive(1), Seed(230).derive(1));
+        assert_ne!(seed.derive(0), seed);
+    }
+
+    #[test]
+    fn text_seeds() {
+        let parse = |text: &str| text.parse::<Seed>().unwrap();
+
+        assert_eq!(parse("12345"), Seed(12345));
+        assert_eq!(parse(" 18446744073709551615 "), Seed(u64::MAX));
+        assert_eq!(parse("-1"), Seed(u64::MAX));
+        assert_eq!(parse("glacier"), parse("glacier"));
+        assert_ne!(parse("glacier"), parse("Glacier"));
+        assert_ne!(parse("abcdefgh"), parse("abcdefgh\0"));
+        assert_ne!(parse(""), parse("\0"));
+
+        for seed in [Seed(0), Seed(u64::MAX), parse("glacier"), Seed::new()].iter() {
+            assert_eq!(parse(&seed.to_string()), *seed);
+        }
+    }
+
+    #[test]
+    fn random_seeds() {
+        assert_ne!(Seed::new(), Seed::new());
+        assert_eq!(Seed(0xdead_beef).to_u32(), 0xdead_beef);
+        assert_ne!(Seed(1 << 32).to_u32(), Seed(0).to_u32());
+    }
+
+    #[test]
+    fn legacy_buffers() {
+        let mut expected = [0; 16];
+        for word in expected.chunks_mut(4) {
+            word.copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+        }
+        assert_eq!(SeedBuffer::from(Seed(0x1234_5678)), SeedBuffer(expected));
+        assert_ne!(
+            SeedBuffer::from(Seed(0x1234_5678)),
+            SeedBuffer::from(Seed(1 << 40 | 0x1234_5678))
+        );
+    }
+
+    #[test]
+    fn expanded_buffers_do_not_repeat() {
+        let buffer = SeedBuffer::expand(0);
+
+        assert_ne!(buffer.0[..8], buffer.0[8..]);
+        assert_ne!(SeedBuffer::expand(1), SeedBuffer::expand(2));
+    }
+}
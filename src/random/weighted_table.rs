@@ -0,0 +1,134 @@
+use crate::random::{Generator, Prng};
+
+/// Values drawn with a probability proportional to their weight, in constant time
+///
+/// Built with Vose's alias method: every slot holds a value and an alias, a draw picks a slot
+/// uniformly then keeps its value or switches to the alias.
+pub struct WeightedTable<T> {
+    values: Vec<T>,
+    /// probability of keeping the value of each slot
+    probabilities: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl<T> WeightedTable<T> {
+    /// Panics when a weight is negative or not finite, or when they are all zero
+    pub fn new<I: IntoIterator<Item = (T, f32)>>(entries: I) -> Self {
+        let (values, weights): (Vec<T>, Vec<f32>) = entries.into_iter().unzip();
+        assert!(
+            weights.iter().all(|w| w.is_finite() && *w >= 0.0),
+            "weights must be positive and finite"
+        );
+
+        let total: f64 = weights.iter().map(|w| *w as f64).sum();
+        assert!(total > 0.0, "at least one weight must be above zero");
+
+        let n = weights.len();
+        let mut probabilities: Vec<f64> = weights
+            .iter()
+            .map(|w| *w as f64 * n as f64 / total)
+            .collect();
+        let mut aliases: Vec<usize> = (0..n).collect();
+
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|&i| probabilities[i] < 1.0);
+
+        // each underfull slot is topped up by a slot with extra weight
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            aliases[s] = l;
+            probabilities[l] -= 1.0 - probabilities[s];
+
+            if probabilities[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+
+        // what is left is full up to rounding errors
+        for i in small.into_iter().chain(large) {
+            probabilities[i] = 1.0;
+        }
+
+        Self {
+            values,
+            probabilities,
+            aliases,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// index of the drawn value, in the order the entries were given
+    pub fn sample_index<G: Generator>(&self, prng: &mut Prng<G>) -> usize {
+        let slot = prng.next_in_range(0..self.values.len());
+
+        if prng.next_f64() < self.probabilities[slot] {
+            slot
+        } else {
+            self.aliases[slot]
+        }
+    }
+
+    pub fn sample<G: Generator>(&self, prng: &mut Prng<G>) -> &T {
+        &self.values[self.sample_index(prng)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Seed;
+
+    #[test]
+    fn frequencies_follow_weights() {
+        let weights = [5.0, 0.0, 1.0, 12.5, 0.5, 1.0];
+        let table = WeightedTable::new(weights.iter().enumerate().map(|(i, w)| (i, *w)));
+        let total: f32 = weights.iter().sum();
+        let mut prng = Prng::new(Seed(167));
+
+        let mut counts = [0u32; 6];
+        let draws = 200_000;
+        for _ in 0..draws {
+            counts[*table.sample(&mut prng)] += 1;
+        }
+        assert_eq!(counts[1], 0);
+
+        // chi-squared over the values that can be drawn, 4 degrees of freedom at p = 0.001
+        let statistic: f64 = counts
+            .iter()
+            .zip(weights.iter())
+            .filter(|(_, w)| **w > 0.0)
+            .map(|(count, w)| {
+                let expected = draws as f64 * *w as f64 / total as f64;
+                (*count as f64 - expected).powi(2) / expected
+            })
+            .sum();
+        assert!(statistic < 18.47);
+    }
+
+    #[test]
+    fn single_entry() {
+        let table = WeightedTable::new(vec![("only", 3.0)]);
+        let mut prng = Prng::new(Seed(173));
+
+        assert_eq!(table.len(), 1);
+        assert!((0..100).all(|_| *table.sample(&mut prng) == "only"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_weights() {
+        WeightedTable::new(vec![(1, 0.0), (2, 0.0)]);
+    }
+}
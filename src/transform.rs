@@ -69,7 +69,7 @@ impl Transform {
     }
 
     pub fn matrix(&self) -> &Matrix4 {
-        &self.m.as_ref().unwrap()
+        self.m.as_ref().unwrap()
     }
 
     fn generate_matrix(&mut self) {
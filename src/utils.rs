@@ -16,3 +16,355 @@ pub fn ternary<T>(condition: bool, a: T, b: T) -> T {
         b
     }
 }
+
+/// spreads the low 16 bits of x over the even bits of the result
+fn part_1_by_1(x: u32) -> u32 {
+    let mut x = x & 0x0000_ffff;
+    x = (x | x << 8) & 0x00ff_00ff;
+    x = (x | x << 4) & 0x0f0f_0f0f;
+    x = (x | x << 2) & 0x3333_3333;
+    x = (x | x << 1) & 0x5555_5555;
+    x
+}
+
+fn compact_1_by_1(x: u32) -> u32 {
+    let mut x = x & 0x5555_5555;
+    x = (x | x >> 1) & 0x3333_3333;
+    x = (x | x >> 2) & 0x0f0f_0f0f;
+    x = (x | x >> 4) & 0x00ff_00ff;
+    x = (x | x >> 8) & 0x0000_ffff;
+    x
+}
+
+fn part_1_by_1_64(x: u64) -> u64 {
+    let mut x = x & 0x0000_0000_ffff_ffff;
+    x = (x | x << 16) & 0x0000_ffff_0000_ffff;
+    x = (x | x << 8) & 0x00ff_00ff_00ff_00ff;
+    x = (x | x << 4) & 0x0f0f_0f0f_0f0f_0f0f;
+    x = (x | x << 2) & 0x3333_3333_3333_3333;
+    x = (x | x << 1) & 0x5555_5555_5555_5555;
+    x
+}
+
+fn compact_1_by_1_64(x: u64) -> u64 {
+    let mut x = x & 0x5555_5555_5555_5555;
+    x = (x | x >> 1) & 0x3333_3333_3333_3333;
+    x = (x | x >> 2) & 0x0f0f_0f0f_0f0f_0f0f;
+    x = (x | x >> 4) & 0x00ff_00ff_00ff_00ff;
+    x = (x | x >> 8) & 0x0000_ffff_0000_ffff;
+    x = (x | x >> 16) & 0x0000_0000_ffff_ffff;
+    x
+}
+
+/// spreads the low 10 bits of x over every third bit of the result
+fn part_1_by_2(x: u32) -> u32 {
+    let mut x = x & 0x0000_03ff;
+    x = (x | x << 16) & 0xff00_00ff;
+    x = (x | x << 8) & 0x0300_f00f;
+    x = (x | x << 4) & 0x030c_30c3;
+    x = (x | x << 2) & 0x0924_9249;
+    x
+}
+
+fn compact_1_by_2(x: u32) -> u32 {
+    let mut x = x & 0x0924_9249;
+    x = (x | x >> 2) & 0x030c_30c3;
+    x = (x | x >> 4) & 0x0300_f00f;
+    x = (x | x >> 8) & 0xff00_00ff;
+    x = (x | x >> 16) & 0x0000_03ff;
+    x
+}
+
+fn part_1_by_2_64(x: u64) -> u64 {
+    let mut x = x & 0x0000_0000_001f_ffff;
+    x = (x | x << 32) & 0x001f_0000_0000_ffff;
+    x = (x | x << 16) & 0x001f_0000_ff00_00ff;
+    x = (x | x << 8) & 0x100f_00f0_0f00_f00f;
+    x = (x | x << 4) & 0x10c3_0c30_c30c_30c3;
+    x = (x | x << 2) & 0x1249_2492_4924_9249;
+    x
+}
+
+fn compact_1_by_2_64(x: u64) -> u64 {
+    let mut x = x & 0x1249_2492_4924_9249;
+    x = (x | x >> 2) & 0x10c3_0c30_c30c_30c3;
+    x = (x | x >> 4) & 0x100f_00f0_0f00_f00f;
+    x = (x | x >> 8) & 0x001f_0000_ff00_00ff;
+    x = (x | x >> 16) & 0x001f_0000_0000_ffff;
+    x = (x | x >> 32) & 0x0000_0000_001f_ffff;
+    x
+}
+
+/// Z-order index of a 2D position, x taking the lowest bit
+pub fn morton_encode2(x: u16, y: u16) -> u32 {
+    part_1_by_1(x as u32) | part_1_by_1(y as u32) << 1
+}
+
+pub fn morton_decode2(code: u32) -> (u16, u16) {
+    (
+        compact_1_by_1(code) as u16,
+        compact_1_by_1(code >> 1) as u16,
+    )
+}
+
+pub fn morton_encode2_64(x: u32, y: u32) -> u64 {
+    part_1_by_1_64(x as u64) | part_1_by_1_64(y as u64) << 1
+}
+
+pub fn morton_decode2_64(code: u64) -> (u32, u32) {
+    (
+        compact_1_by_1_64(code) as u32,
+        compact_1_by_1_64(code >> 1) as u32,
+    )
+}
+
+/// Z-order index of a 3D position, each coordinate below 2^10
+pub fn morton_encode3(x: u32, y: u32, z: u32) -> u32 {
+    debug_assert!(
+        x.max(y).max(z) < 1 << 10,
+        "coordinates ({}, {}, {}) do not fit in 10 bits",
+        x,
+        y,
+        z
+    );
+    part_1_by_2(x) | part_1_by_2(y) << 1 | part_1_by_2(z) << 2
+}
+
+pub fn morton_decode3(code: u32) -> (u32, u32, u32) {
+    (
+        compact_1_by_2(code),
+        compact_1_by_2(code >> 1),
+        compact_1_by_2(code >> 2),
+    )
+}
+
+/// Z-order index of a 3D position, each coordinate below 2^21
+pub fn morton_encode3_64(x: u32, y: u32, z: u32) -> u64 {
+    debug_assert!(
+        x.max(y).max(z) < 1 << 21,
+        "coordinates ({}, {}, {}) do not fit in 21 bits",
+        x,
+        y,
+        z
+    );
+    part_1_by_2_64(x as u64) | part_1_by_2_64(y as u64) << 1 | part_1_by_2_64(z as u64) << 2
+}
+
+pub fn morton_decode3_64(code: u64) -> (u32, u32, u32) {
+    (
+        compact_1_by_2_64(code) as u32,
+        compact_1_by_2_64(code >> 1) as u32,
+        compact_1_by_2_64(code >> 2) as u32,
+    )
+}
+
+/// Index along the Hilbert curve filling a square of 2^order cells per side, order <= 32
+pub fn hilbert_encode2(x: u32, y: u32, order: u32) -> u64 {
+    let mut axes = [x, y];
+    axes_to_transpose(&mut axes, order);
+    interleave(&axes, order)
+}
+
+pub fn hilbert_decode2(index: u64, order: u32) -> (u32, u32) {
+    let mut axes = [0; 2];
+    deinterleave(index, &mut axes, order);
+    transpose_to_axes(&mut axes, order);
+    (axes[0], axes[1])
+}
+
+/// Index along the Hilbert curve filling a cube of 2^order cells per side, order <= 21
+pub fn hilbert_encode3(x: u32, y: u32, z: u32, order: u32) -> u64 {
+    let mut axes = [x, y, z];
+    axes_to_transpose(&mut axes, order);
+    interleave(&axes, order)
+}
+
+pub fn hilbert_decode3(index: u64, order: u32) -> (u32, u32, u32) {
+    let mut axes = [0; 3];
+    deinterleave(index, &mut axes, order);
+    transpose_to_axes(&mut axes, order);
+    (axes[0], axes[1], axes[2])
+}
+
+/// Skilling's transform of coordinates into the transposed Hilbert index
+fn axes_to_transpose(x: &mut [u32], order: u32) {
+    if order == 0 {
+        return;
+    }
+
+    let n = x.len();
+    let mut q = 1u32 << (order - 1);
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..n {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // gray encode
+    for i in 1..n {
+        x[i] ^= x[i - 1];
+    }
+    let mut t = 0;
+    let mut q = 1u32 << (order - 1);
+    while q > 1 {
+        if x[n - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for value in x.iter_mut() {
+        *value ^= t;
+    }
+}
+
+fn transpose_to_axes(x: &mut [u32], order: u32) {
+    if order == 0 {
+        return;
+    }
+
+    // gray decode
+    let n = x.len();
+    let t = x[n - 1] >> 1;
+    for i in (1..n).rev() {
+        x[i] ^= x[i - 1];
+    }
+    x[0] ^= t;
+
+    let mut q = 2u64;
+    while q != 1u64 << order {
+        let p = (q - 1) as u32;
+        for i in (0..n).rev() {
+            if x[i] & q as u32 != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q <<= 1;
+    }
+}
+
+/// bits of the axes from the most significant, the first axis taking the highest bit of each
+/// group
+fn interleave(axes: &[u32], order: u32) -> u64 {
+    let mut index = 0u64;
+    for bit in (0..order).rev() {
+        for axis in axes {
+            index = index << 1 | (axis >> bit & 1) as u64;
+        }
+    }
+    index
+}
+
+fn deinterleave(mut index: u64, axes: &mut [u32], order: u32) {
+    for bit in 0..order {
+        for axis in axes.iter_mut().rev() {
+            *axis |= ((index & 1) as u32) << bit;
+            index >>= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn morton_round_trip() {
+        for x in 0..64u16 {
+            for y in 0..64u16 {
+                let code = morton_encode2(x, y);
+                assert_eq!(morton_decode2(code), (x, y));
+                assert_eq!(morton_encode2_64(x as u32, y as u32), code as u64);
+                assert_eq!(morton_decode2_64(code as u64), (x as u32, y as u32));
+
+                for z in 0..16 {
+                    let (x, y) = (x as u32, y as u32);
+                    let code = morton_encode3(x, y, z);
+                    assert_eq!(morton_decode3(code), (x, y, z));
+                    assert_eq!(morton_encode3_64(x, y, z), code as u64);
+                    assert_eq!(morton_decode3_64(code as u64), (x, y, z));
+                }
+            }
+        }
+
+        assert_eq!(morton_encode2(0xffff, 0xffff), u32::MAX);
+        assert_eq!(morton_decode2_64(u64::MAX), (u32::MAX, u32::MAX));
+        assert_eq!(morton_encode3(0x3ff, 0x3ff, 0x3ff), (1 << 30) - 1);
+        assert_eq!(
+            morton_decode3_64((1 << 63) - 1),
+            (0x1fffff, 0x1fffff, 0x1fffff)
+        );
+    }
+
+    #[test]
+    fn morton_ordering() {
+        assert_eq!(morton_encode2(1, 0), 1);
+        assert_eq!(morton_encode2(0, 1), 2);
+        assert_eq!(morton_encode3(0, 0, 1), 4);
+        assert_eq!(morton_encode3_64(3, 0, 0), 9);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn morton3_out_of_range() {
+        morton_encode3(0, 1 << 10, 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn morton3_64_out_of_range() {
+        morton_encode3_64(0, 0, 1 << 21);
+    }
+
+    #[test]
+    fn hilbert_is_continuous() {
+        for order in 1..6 {
+            let side = 1u32 << order;
+            let cells = (side * side) as u64;
+            let mut previous = hilbert_decode2(0, order);
+
+            for index in 1..cells {
+                let (x, y) = hilbert_decode2(index, order);
+                assert_eq!(hilbert_encode2(x, y, order), index);
+
+                let distance =
+                    (x as i64 - previous.0 as i64).abs() + (y as i64 - previous.1 as i64).abs();
+                assert_eq!(distance, 1);
+                previous = (x, y);
+            }
+        }
+
+        for order in 1..5 {
+            let side = 1u32 << order;
+            let cells = (side * side * side) as u64;
+            let mut previous = hilbert_decode3(0, order);
+
+            for index in 1..cells {
+                let (x, y, z) = hilbert_decode3(index, order);
+                assert_eq!(hilbert_encode3(x, y, z, order), index);
+
+                let distance = (x as i64 - previous.0 as i64).abs()
+                    + (y as i64 - previous.1 as i64).abs()
+                    + (z as i64 - previous.2 as i64).abs();
+                assert_eq!(distance, 1);
+                previous = (x, y, z);
+            }
+        }
+
+        assert_eq!(hilbert_decode2(0, 4), (0, 0));
+        assert_eq!(hilbert_encode2(u32::MAX, 0, 32), u64::MAX);
+    }
+}
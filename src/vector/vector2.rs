@@ -45,6 +45,16 @@ impl Vector2 {
         (Vector2::dot(a, b) / Vector2::dot(b, b)) * b
     }
 
+    /// component-wise minimum
+    pub fn min(a: Self, b: Self) -> Self {
+        Self::new(a.x.min(b.x), a.y.min(b.y))
+    }
+
+    /// component-wise maximum
+    pub fn max(a: Self, b: Self) -> Self {
+        Self::new(a.x.max(b.x), a.y.max(b.y))
+    }
+
     pub fn magnitude(self) -> f32 {
         Self::dot(self, self).sqrt()
     }
@@ -63,6 +63,20 @@ impl Vector3 {
         (Vector3::dot(a, b) / Vector3::dot(b, b)) * b
     }
 
+    /// component-wise minimum
+    pub fn min(a: Self, b: Self) -> Self {
+        Self::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z))
+    }
+
+    /// component-wise maximum
+    pub fn max(a: Self, b: Self) -> Self {
+        Self::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))
+    }
+
+    pub fn abs(self) -> Self {
+        Self::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
     pub fn magnitude(self) -> f32 {
         Self::dot(self, self).sqrt()
     }